@@ -0,0 +1,270 @@
+use super::{Diagnostic, DiagnosticResult};
+use crate::core::MemoryFile;
+use anyhow::{bail, Context, Result};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_BASELINE_FILE: &str = ".ccat-baseline.json";
+
+const BASELINE_VERSION: u32 = 1;
+
+static DIGITS_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\d+").unwrap());
+
+/// Known findings recorded with `diagnose --write-baseline`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Baseline {
+    pub version: u32,
+    pub entries: Vec<BaselineEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BaselineEntry {
+    pub rule: String,
+    pub file: String,
+    /// Hash of the rule, file and normalized content of the flagged line, so
+    /// entries survive edits elsewhere in the file.
+    pub fingerprint: String,
+    pub message: String,
+}
+
+/// Outcome of comparing a run against a baseline.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BaselineSummary {
+    /// Diagnostics hidden because they are in the baseline.
+    pub suppressed: usize,
+    /// Baseline entries with no matching diagnostic anymore.
+    pub fixed: Vec<BaselineEntry>,
+}
+
+impl Baseline {
+    pub fn from_result(
+        result: &DiagnosticResult,
+        files: &[MemoryFile],
+        project_root: &Path,
+    ) -> Self {
+        Self {
+            version: BASELINE_VERSION,
+            entries: result
+                .diagnostics()
+                .map(|diagnostic| entry(diagnostic, files, project_root))
+                .collect(),
+        }
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read baseline: {}", path.display()))?;
+        let baseline: Self = serde_json::from_str(&content)
+            .with_context(|| format!("Invalid baseline: {}", path.display()))?;
+        if baseline.version != BASELINE_VERSION {
+            bail!(
+                "Unsupported baseline version {} in {}",
+                baseline.version,
+                path.display()
+            );
+        }
+        Ok(baseline)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(path, format!("{}\n", json))
+            .with_context(|| format!("Failed to write baseline: {}", path.display()))
+    }
+
+    /// Drops diagnostics recorded in the baseline from `result` and records
+    /// which entries no longer occur. Each entry matches one diagnostic, so a
+    /// new copy of a known finding is still reported.
+    pub fn apply(&self, result: &mut DiagnosticResult, files: &[MemoryFile], project_root: &Path) {
+        let mut remaining: HashMap<&str, Vec<&BaselineEntry>> = HashMap::new();
+        for entry in &self.entries {
+            remaining.entry(&entry.fingerprint).or_default().push(entry);
+        }
+
+        let mut suppressed = 0;
+        for diagnostics in [&mut result.errors, &mut result.warnings, &mut result.infos] {
+            diagnostics.retain(|diagnostic| {
+                let fingerprint = entry(diagnostic, files, project_root).fingerprint;
+                match remaining.get_mut(fingerprint.as_str()) {
+                    Some(entries) if !entries.is_empty() => {
+                        entries.pop();
+                        suppressed += 1;
+                        false
+                    }
+                    _ => true,
+                }
+            });
+        }
+
+        let mut fixed: Vec<BaselineEntry> = remaining.into_values().flatten().cloned().collect();
+        fixed.sort_by(|a, b| (&a.file, &a.rule).cmp(&(&b.file, &b.rule)));
+
+        result.baseline = Some(BaselineSummary { suppressed, fixed });
+    }
+}
+
+fn entry(diagnostic: &Diagnostic, files: &[MemoryFile], project_root: &Path) -> BaselineEntry {
+    let file = diagnostic
+        .file
+        .as_deref()
+        .map(|file| normalize_path(file, project_root))
+        .unwrap_or_default();
+
+    let line = diagnostic.line.and_then(|line| {
+        let path = Path::new(diagnostic.file.as_deref()?);
+        let canonical = path.canonicalize().ok();
+        let memory = files.iter().find(|f| {
+            f.path == path || (canonical.is_some() && f.path.canonicalize().ok() == canonical)
+        })?;
+        memory
+            .content
+            .lines()
+            .nth(line.checked_sub(1)?)
+            .map(normalize_text)
+    });
+    // Messages often mention line numbers and absolute paths, which must not
+    // affect the match
+    let message = normalize_message(&diagnostic.message, project_root);
+    let pattern = DIGITS_REGEX.replace_all(&message, "#");
+
+    let mut hasher = Sha256::new();
+    for part in [
+        diagnostic.code.as_str(),
+        &file,
+        line.as_deref().unwrap_or_default(),
+        &pattern,
+    ] {
+        hasher.update(part.as_bytes());
+        hasher.update([0]);
+    }
+    let fingerprint = format!("{:x}", hasher.finalize())[..16].to_string();
+
+    BaselineEntry {
+        rule: diagnostic.code.clone(),
+        file,
+        fingerprint,
+        message,
+    }
+}
+
+/// Directories paths are made relative to, with the prefix replacing them:
+/// the project root and the home directory, each as given and canonical.
+fn roots(project_root: &Path) -> Vec<(PathBuf, &'static str)> {
+    let mut roots = vec![(project_root.to_path_buf(), "")];
+    roots.extend(project_root.canonicalize().ok().map(|root| (root, "")));
+    if let Some(home) = dirs::home_dir() {
+        roots.extend(home.canonicalize().ok().map(|home| (home, "~/")));
+        roots.push((home, "~/"));
+    }
+    roots
+}
+
+/// Paths relative to the project root, or to `~` for files in the home
+/// directory, so a baseline is portable between checkouts. Rules that follow
+/// imports report canonical paths, so the canonical root is stripped too.
+fn normalize_path(path: &str, project_root: &Path) -> String {
+    let path = Path::new(path);
+    roots(project_root)
+        .into_iter()
+        .find_map(|(root, prefix)| {
+            let relative = path.strip_prefix(root).ok()?;
+            Some(format!("{}{}", prefix, relative.display()))
+        })
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+/// `message` with the absolute paths it mentions made relative like
+/// `normalize_path` does.
+fn normalize_message(message: &str, project_root: &Path) -> String {
+    roots(project_root)
+        .into_iter()
+        .filter(|(root, _)| root.is_absolute() && root.parent().is_some())
+        .fold(message.to_string(), |message, (root, prefix)| {
+            let root = format!("{}{}", root.display(), std::path::MAIN_SEPARATOR);
+            message.replace(&root, prefix)
+        })
+}
+
+fn normalize_text(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::{DiagnosticLevel, PerformanceMetrics};
+
+    fn result(diagnostics: Vec<Diagnostic>) -> DiagnosticResult {
+        let mut result = DiagnosticResult {
+            errors: Vec::new(),
+            warnings: Vec::new(),
+            infos: Vec::new(),
+            suggestions: Vec::new(),
+            metrics: PerformanceMetrics::default(),
+            baseline: None,
+        };
+        for diagnostic in diagnostics {
+            result.push(diagnostic);
+        }
+        result
+    }
+
+    /// A circular import found in a checkout at `root`, reported with
+    /// canonical paths.
+    fn cycle(root: &Path) -> Diagnostic {
+        let root = root.canonicalize().unwrap();
+        let (memory, notes) = (root.join("CLAUDE.md"), root.join("notes.md"));
+        Diagnostic::new(
+            DiagnosticLevel::Error,
+            "circular-import",
+            format!(
+                "Circular import: {} -> {} -> {}",
+                memory.display(),
+                notes.display(),
+                memory.display()
+            ),
+        )
+        .with_file(&memory)
+        .with_line(3)
+    }
+
+    #[test]
+    fn baseline_is_portable_between_checkouts() {
+        let (first, second) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+
+        let baseline = Baseline::from_result(&result(vec![cycle(first.path())]), &[], first.path());
+        let entry = &baseline.entries[0];
+        assert_eq!(entry.file, "CLAUDE.md");
+        assert_eq!(
+            entry.message,
+            "Circular import: CLAUDE.md -> notes.md -> CLAUDE.md"
+        );
+
+        let mut moved = result(vec![cycle(second.path()), cycle(second.path())]);
+        baseline.apply(&mut moved, &[], second.path());
+        // Each entry hides one diagnostic only
+        assert_eq!(moved.errors.len(), 1);
+        let summary = moved.baseline.unwrap();
+        assert_eq!(summary.suppressed, 1);
+        assert!(summary.fixed.is_empty());
+    }
+
+    #[test]
+    fn unmatched_entries_are_reported_as_fixed() {
+        let dir = tempfile::tempdir().unwrap();
+        let baseline = Baseline::from_result(&result(vec![cycle(dir.path())]), &[], dir.path());
+
+        let mut clean = result(Vec::new());
+        baseline.apply(&mut clean, &[], dir.path());
+        let summary = clean.baseline.unwrap();
+        assert_eq!(summary.suppressed, 0);
+        assert_eq!(summary.fixed, baseline.entries);
+    }
+}
@@ -0,0 +1,252 @@
+use super::tokens::CHARS_PER_TOKEN;
+use crate::core::parser::Parser;
+use crate::core::{
+    Config, ImportResolver, MemoryFile, MemoryType, Scanner, SourceMap, MAX_IMPORT_DEPTH,
+};
+use anyhow::Result;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Everything Claude Code loads when started in `directory`: the user memory,
+/// memory files of every ancestor directory, the project memory and the
+/// subdirectory memories down to `directory`, with their imports expanded.
+#[derive(Debug, Clone)]
+pub struct EffectiveContext {
+    pub directory: PathBuf,
+    /// Memory files in load order, most general first.
+    pub memory_files: Vec<PathBuf>,
+    pub tokens: usize,
+    /// Files in the expanded context, largest first. Imported files count
+    /// separately from the files importing them.
+    pub files: Vec<Contribution>,
+    /// Sections of those files, largest first.
+    pub sections: Vec<Contribution>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Contribution {
+    pub file: PathBuf,
+    /// Heading of the section; `None` for whole files and for the text
+    /// before the first heading.
+    pub section: Option<String>,
+    pub line: usize,
+    pub tokens: usize,
+}
+
+pub struct ContextAnalyzer<'a> {
+    project_root: &'a Path,
+    config: &'a Config,
+}
+
+impl<'a> ContextAnalyzer<'a> {
+    pub fn new(project_root: &'a Path, config: &'a Config) -> Self {
+        Self {
+            project_root,
+            config,
+        }
+    }
+
+    /// One effective context per directory holding a scanned project,
+    /// local or subdirectory memory file, or the error that kept the imports
+    /// of that directory from being expanded.
+    pub fn analyze(&self, files: &[MemoryFile]) -> Vec<(PathBuf, Result<EffectiveContext>)> {
+        let ancestors = self.ancestor_memory();
+
+        let mut directories: Vec<&Path> = files
+            .iter()
+            .filter(|f| f.file_type != MemoryType::UserMemory)
+            .filter_map(|f| f.path.parent())
+            .collect();
+        directories.sort();
+        directories.dedup();
+
+        directories
+            .into_iter()
+            .map(|directory| {
+                let context = self.context_for(directory, files, &ancestors);
+                (directory.to_path_buf(), context)
+            })
+            .collect()
+    }
+
+    fn context_for(
+        &self,
+        directory: &Path,
+        files: &[MemoryFile],
+        ancestors: &[MemoryFile],
+    ) -> Result<EffectiveContext> {
+        let mut chain: Vec<MemoryFile> = files
+            .iter()
+            .filter(|f| f.file_type == MemoryType::UserMemory)
+            .cloned()
+            .collect();
+        chain.extend(ancestors.iter().cloned());
+
+        let mut applicable: Vec<&MemoryFile> = files
+            .iter()
+            .filter(|f| f.file_type != MemoryType::UserMemory)
+            .filter(|f| {
+                f.path
+                    .parent()
+                    .is_some_and(|dir| directory.starts_with(dir))
+            })
+            .collect();
+        applicable.sort_by_key(|f| f.precedence());
+        chain.extend(applicable.into_iter().cloned());
+
+        let mut resolver = ImportResolver::from_config(self.project_root, self.config);
+        let context = resolver.expand_all(&chain, MAX_IMPORT_DEPTH)?;
+        let (tokens, files, sections) = attribute(&context.content, &context.source_map, &chain);
+
+        Ok(EffectiveContext {
+            directory: directory.to_path_buf(),
+            memory_files: chain.into_iter().map(|f| f.path).collect(),
+            tokens,
+            files,
+            sections,
+        })
+    }
+
+    /// Memory files in the directories above the project root, outermost
+    /// first, with their imports resolved.
+    fn ancestor_memory(&self) -> Vec<MemoryFile> {
+        let Ok(root) = self.project_root.canonicalize() else {
+            return Vec::new();
+        };
+
+        let mut memory = Vec::new();
+        for dir in root.ancestors().skip(1) {
+            let Ok(found) = Scanner::new().scan(dir) else {
+                continue;
+            };
+            // Imports of these files are checked against their own directory,
+            // as if ccat had been run there
+            let mut resolver = ImportResolver::from_config(dir, self.config);
+            for mut file in found
+                .into_iter()
+                .filter(|f| f.file_type != MemoryType::UserMemory)
+                .rev()
+            {
+                if resolver.resolve_imports(&mut file).is_ok() {
+                    memory.push(file);
+                }
+            }
+        }
+
+        memory.reverse();
+        memory
+    }
+}
+
+/// Splits the expanded context into per-file and per-section token counts.
+/// Markers inserted by the expansion itself are not counted.
+fn attribute(
+    content: &str,
+    source_map: &SourceMap,
+    chain: &[MemoryFile],
+) -> (usize, Vec<Contribution>, Vec<Contribution>) {
+    let mut file_chars: HashMap<&Path, usize> = HashMap::new();
+    let mut section_chars: HashMap<(&Path, usize), usize> = HashMap::new();
+    let mut headings: HashMap<&Path, Vec<(usize, String)>> = HashMap::new();
+    let mut total = 0;
+
+    for (line, location) in content.lines().zip(&source_map.mappings) {
+        if location.generated {
+            continue;
+        }
+        let chars = line.chars().count() + 1;
+        let file = location.file.as_path();
+        total += chars;
+        *file_chars.entry(file).or_default() += chars;
+
+        let file_headings = headings.entry(file).or_insert_with(|| {
+            let content = chain
+                .iter()
+                .find(|f| f.path == file)
+                .map(|f| f.content.clone())
+                .or_else(|| fs::read_to_string(file).ok())
+                .unwrap_or_default();
+            Parser::new()
+                .parse_content(&content)
+                .map(|parsed| {
+                    parsed
+                        .sections
+                        .into_iter()
+                        .map(|s| (s.line_start, s.title))
+                        .collect()
+                })
+                .unwrap_or_default()
+        });
+        let section_start = file_headings
+            .iter()
+            .rev()
+            .find(|(start, _)| *start <= location.line)
+            .map_or(0, |(start, _)| *start);
+        *section_chars.entry((file, section_start)).or_default() += chars;
+    }
+
+    let tokens = |chars: usize| chars.div_ceil(CHARS_PER_TOKEN);
+
+    let mut files: Vec<Contribution> = file_chars
+        .into_iter()
+        .map(|(file, chars)| Contribution {
+            file: file.to_path_buf(),
+            section: None,
+            line: 1,
+            tokens: tokens(chars),
+        })
+        .collect();
+    files.sort_by(|a, b| b.tokens.cmp(&a.tokens).then_with(|| a.file.cmp(&b.file)));
+
+    let mut sections: Vec<Contribution> = section_chars
+        .into_iter()
+        .map(|((file, start), chars)| Contribution {
+            file: file.to_path_buf(),
+            section: headings[file]
+                .iter()
+                .find(|(line, _)| *line == start)
+                .map(|(_, title)| title.clone()),
+            line: start.max(1),
+            tokens: tokens(chars),
+        })
+        .collect();
+    sections.sort_by(|a, b| {
+        b.tokens
+            .cmp(&a.tokens)
+            .then_with(|| (&a.file, a.line).cmp(&(&b.file, b.line)))
+    });
+
+    (tokens(total), files, sections)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_context_of_circular_imports() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("CLAUDE.md"), "# Project\n\n@notes.md\n").unwrap();
+        fs::write(dir.path().join("notes.md"), "# Notes\n\n@CLAUDE.md\n").unwrap();
+
+        let config = Config::default();
+        let mut files = Scanner::new().scan(dir.path()).unwrap();
+        ImportResolver::from_config(dir.path(), &config)
+            .resolve_all(&mut files)
+            .unwrap();
+
+        let contexts = ContextAnalyzer::new(dir.path(), &config).analyze(&files);
+        assert_eq!(contexts.len(), 1);
+        let (directory, context) = &contexts[0];
+        assert_eq!(directory, dir.path());
+        let context = context.as_ref().unwrap();
+        let counted: Vec<_> = context
+            .files
+            .iter()
+            .filter_map(|c| c.file.file_name())
+            .collect();
+        assert!(counted.contains(&"notes.md".as_ref()), "{:?}", counted);
+        assert!(context.tokens > 0);
+    }
+}
@@ -0,0 +1,465 @@
+use crate::core::{ExpandedContext, MemoryFile, MemoryType};
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+// Matches placed after one of these in the same clause are not instructions,
+// e.g. "use pnpm, not npm" or "never indent with tabs".
+static NEGATION_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b(?:don'?t|do not|never|avoid|no|not|instead of|rather than|over)\b").unwrap()
+});
+
+/// Something memory files can give opposing instructions about, with one
+/// pattern per possible choice.
+#[derive(Debug, Clone)]
+pub struct Topic {
+    pub name: String,
+    pub choices: Vec<Choice>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Choice {
+    pub label: String,
+    pub pattern: Regex,
+}
+
+impl Topic {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            choices: Vec::new(),
+        }
+    }
+
+    /// Adds a choice; `pattern` is matched case-insensitively.
+    pub fn with_choice(mut self, label: impl Into<String>, pattern: &str) -> Result<Self> {
+        let label = label.into();
+        let pattern = Regex::new(&format!("(?i){}", pattern))
+            .with_context(|| format!("Invalid pattern for {} / {}", self.name, label))?;
+        self.choices.push(Choice { label, pattern });
+        Ok(self)
+    }
+}
+
+/// An instruction matching one choice of a topic.
+#[derive(Debug, Clone)]
+pub struct Mention {
+    pub file: PathBuf,
+    pub line: usize,
+    pub choice: String,
+    pub text: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct Contradiction {
+    pub topic: String,
+    /// Mention in the file that takes precedence (or the later line when both
+    /// are in the same file).
+    pub winner: Mention,
+    pub overridden: Mention,
+}
+
+impl Contradiction {
+    pub fn same_file(&self) -> bool {
+        self.winner.file == self.overridden.file
+    }
+}
+
+pub struct ContradictionDetector {
+    topics: Vec<Topic>,
+}
+
+impl Default for ContradictionDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ContradictionDetector {
+    /// Detector with the built-in topic table.
+    pub fn new() -> Self {
+        Self {
+            topics: builtin_topics(),
+        }
+    }
+
+    pub fn with_topic(mut self, topic: Topic) -> Self {
+        self.topics.push(topic);
+        self
+    }
+
+    pub fn topics(&self) -> &[Topic] {
+        &self.topics
+    }
+
+    /// Finds instructions that pick different choices for the same topic in
+    /// files whose scopes overlap, without following imports.
+    pub fn find(&self, files: &[MemoryFile]) -> Vec<Contradiction> {
+        let expanded: Vec<(&MemoryFile, ExpandedContext)> = files
+            .iter()
+            .map(|file| (file, ExpandedContext::unexpanded(file)))
+            .collect();
+        self.find_expanded(&expanded)
+    }
+
+    /// Like `find`, but over each memory file with its imports expanded.
+    /// Imported instructions take the precedence of the memory file that
+    /// loads them, and within one memory file later lines win. Mentions are
+    /// reported at the file and line they come from. Each overridden mention
+    /// is paired with the last contradicting mention of every file that
+    /// takes precedence over it.
+    pub fn find_expanded(&self, files: &[(&MemoryFile, ExpandedContext)]) -> Vec<Contradiction> {
+        let mut contradictions = Vec::new();
+        let mut seen = HashSet::new();
+
+        for topic in &self.topics {
+            let mentions: Vec<Located> = files
+                .iter()
+                .flat_map(|(memory, context)| mentions(memory, context, topic))
+                .collect();
+
+            for located in &mentions {
+                // Latest contradicting mention in each file that outranks this one
+                let mut winners: BTreeMap<&Path, &Located> = BTreeMap::new();
+                for other in &mentions {
+                    if other.mention.choice != located.mention.choice
+                        && scopes_overlap(located.memory, other.memory)
+                        && outranks(other, located)
+                    {
+                        let winner = winners.entry(other.mention.file.as_path()).or_insert(other);
+                        if outranks(other, winner) {
+                            *winner = other;
+                        }
+                    }
+                }
+
+                for winner in winners.into_values() {
+                    // A file imported by several memory files is reported once
+                    let key = (
+                        &topic.name,
+                        winner.mention.file.clone(),
+                        winner.mention.line,
+                        located.mention.file.clone(),
+                        located.mention.line,
+                    );
+                    if seen.insert(key) {
+                        contradictions.push(Contradiction {
+                            topic: topic.name.clone(),
+                            winner: winner.mention.clone(),
+                            overridden: located.mention.clone(),
+                        });
+                    }
+                }
+            }
+        }
+
+        contradictions
+    }
+}
+
+/// A mention found in the expanded context of `memory`, at `position` in it.
+struct Located<'a> {
+    memory: &'a MemoryFile,
+    position: usize,
+    mention: Mention,
+}
+
+fn mentions<'a>(
+    memory: &'a MemoryFile,
+    context: &ExpandedContext,
+    topic: &Topic,
+) -> Vec<Located<'a>> {
+    let mut mentions = Vec::new();
+    let mut in_code_block = false;
+
+    for (position, (line, location)) in context
+        .content
+        .lines()
+        .zip(&context.source_map.mappings)
+        .enumerate()
+    {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block || location.generated {
+            continue;
+        }
+
+        for choice in &topic.choices {
+            let found = choice
+                .pattern
+                .find_iter(line)
+                .find(|m| !is_negated(&line[..m.start()]));
+            if let Some(found) = found {
+                mentions.push(Located {
+                    memory,
+                    position,
+                    mention: Mention {
+                        file: location.file.clone(),
+                        line: location.line,
+                        choice: choice.label.clone(),
+                        text: found.as_str().to_string(),
+                    },
+                });
+            }
+        }
+    }
+
+    mentions
+}
+
+/// Whether `a` takes precedence over `b`: the more specific memory file
+/// wins, and within the context of one memory file the later line.
+fn outranks(a: &Located, b: &Located) -> bool {
+    if std::ptr::eq(a.memory, b.memory) {
+        a.position > b.position
+    } else {
+        (a.memory.precedence(), &a.memory.path) > (b.memory.precedence(), &b.memory.path)
+    }
+}
+
+fn is_negated(prefix: &str) -> bool {
+    let clause = prefix
+        .rfind(['.', ',', ';', ':'])
+        .map_or(prefix, |idx| &prefix[idx + 1..]);
+    NEGATION_REGEX.is_match(clause)
+}
+
+/// The user memory applies everywhere; other memories apply to their own
+/// directory and everything below it.
+fn scopes_overlap(a: &MemoryFile, b: &MemoryFile) -> bool {
+    if a.file_type == MemoryType::UserMemory || b.file_type == MemoryType::UserMemory {
+        return true;
+    }
+    let dir_a = a.path.parent().unwrap_or(Path::new(""));
+    let dir_b = b.path.parent().unwrap_or(Path::new(""));
+    dir_a.starts_with(dir_b) || dir_b.starts_with(dir_a)
+}
+
+fn builtin_topics() -> Vec<Topic> {
+    let table: &[(&str, &[(&str, &str)])] = &[
+        (
+            "indentation",
+            &[
+                (
+                    "tabs",
+                    r"\b(?:use|indent with|indented with|prefer)\s+(?:hard\s+)?tabs\b|\btab indentation\b",
+                ),
+                ("2 spaces", r"\b(?:2|two)[- ]spaces?\b"),
+                ("4 spaces", r"\b(?:4|four)[- ]spaces?\b"),
+            ],
+        ),
+        (
+            "quote style",
+            &[
+                ("single quotes", r"\bsingle[- ]quot(?:es?|ed)\b"),
+                ("double quotes", r"\bdouble[- ]quot(?:es?|ed)\b"),
+            ],
+        ),
+        (
+            "package manager",
+            &[
+                (
+                    "npm",
+                    r"\b(?:use|using|prefer|run\s+with|install\s+with)\s+npm\b",
+                ),
+                (
+                    "yarn",
+                    r"\b(?:use|using|prefer|run\s+with|install\s+with)\s+yarn\b",
+                ),
+                (
+                    "pnpm",
+                    r"\b(?:use|using|prefer|run\s+with|install\s+with)\s+pnpm\b",
+                ),
+                (
+                    "bun",
+                    r"\b(?:use|using|prefer|run\s+with|install\s+with)\s+bun\b",
+                ),
+            ],
+        ),
+        (
+            "JavaScript test runner",
+            &[
+                ("jest", r"\bjest\b"),
+                ("vitest", r"\bvitest\b"),
+                ("mocha", r"\bmocha\b"),
+            ],
+        ),
+        (
+            "Python test runner",
+            &[("pytest", r"\bpytest\b"), ("unittest", r"\bunittest\b")],
+        ),
+        (
+            "programming language",
+            &[
+                (
+                    "TypeScript",
+                    r"\b(?:use|write|written in|prefer)\s+typescript\b",
+                ),
+                (
+                    "JavaScript",
+                    r"\b(?:use|write|written in|prefer)\s+(?:plain\s+)?javascript\b",
+                ),
+            ],
+        ),
+        (
+            "response language",
+            &[
+                (
+                    "English",
+                    r"\b(?:respond|reply|answer|write|communicate)\s+in\s+english\b",
+                ),
+                (
+                    "Japanese",
+                    r"\b(?:respond|reply|answer|write|communicate)\s+in\s+japanese\b",
+                ),
+                (
+                    "Chinese",
+                    r"\b(?:respond|reply|answer|write|communicate)\s+in\s+chinese\b",
+                ),
+                (
+                    "Korean",
+                    r"\b(?:respond|reply|answer|write|communicate)\s+in\s+korean\b",
+                ),
+                (
+                    "Spanish",
+                    r"\b(?:respond|reply|answer|write|communicate)\s+in\s+spanish\b",
+                ),
+                (
+                    "French",
+                    r"\b(?:respond|reply|answer|write|communicate)\s+in\s+french\b",
+                ),
+                (
+                    "German",
+                    r"\b(?:respond|reply|answer|write|communicate)\s+in\s+german\b",
+                ),
+            ],
+        ),
+    ];
+
+    table
+        .iter()
+        .map(|(name, choices)| {
+            choices
+                .iter()
+                .try_fold(Topic::new(*name), |topic, (label, pattern)| {
+                    topic.with_choice(*label, pattern)
+                })
+                .unwrap()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{ImportResolver, MAX_IMPORT_DEPTH};
+
+    fn topics(files: &[MemoryFile]) -> Vec<(String, String, String)> {
+        ContradictionDetector::new()
+            .find(files)
+            .into_iter()
+            .map(|c| (c.topic, c.winner.choice, c.overridden.choice))
+            .collect()
+    }
+
+    #[test]
+    fn more_specific_file_wins() {
+        let files = [
+            MemoryFile::from_content(
+                "/home/me/.claude/CLAUDE.md",
+                MemoryType::UserMemory,
+                "Use npm for everything.\n",
+            ),
+            MemoryFile::from_content(
+                "/repo/CLAUDE.md",
+                MemoryType::ProjectMemory,
+                "Always use pnpm, not npm.\n",
+            ),
+        ];
+        assert_eq!(
+            topics(&files),
+            vec![(
+                "package manager".to_string(),
+                "pnpm".to_string(),
+                "npm".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn later_line_wins_within_a_file() {
+        let files = [MemoryFile::from_content(
+            "/repo/CLAUDE.md",
+            MemoryType::ProjectMemory,
+            "Indent with tabs.\n\n```\nuse 4 spaces\n```\nUse 2 spaces.\n",
+        )];
+        let found = ContradictionDetector::new().find(&files);
+        assert_eq!(found.len(), 1);
+        assert_eq!((found[0].winner.line, found[0].overridden.line), (6, 1));
+        assert!(found[0].same_file());
+    }
+
+    #[test]
+    fn package_managers_need_an_instruction() {
+        let files = [
+            MemoryFile::from_content(
+                "/repo/CLAUDE.md",
+                MemoryType::ProjectMemory,
+                "Use pnpm.\nThe npm registry mirror is slow.\n",
+            ),
+            MemoryFile::from_content(
+                "/repo/web/CLAUDE.md",
+                MemoryType::SubdirMemory,
+                "Bun is faster, but yarn workspaces are set up.\n",
+            ),
+        ];
+        assert!(topics(&files).is_empty());
+    }
+
+    #[test]
+    fn unrelated_subdirectories_do_not_conflict() {
+        let files = [
+            MemoryFile::from_content("/repo/a/CLAUDE.md", MemoryType::SubdirMemory, "Use yarn.\n"),
+            MemoryFile::from_content("/repo/b/CLAUDE.md", MemoryType::SubdirMemory, "Use bun.\n"),
+        ];
+        assert!(topics(&files).is_empty());
+    }
+
+    #[test]
+    fn finds_contradictions_with_imported_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("docs")).unwrap();
+        std::fs::write(
+            dir.path().join("docs/style.md"),
+            "# Style\nUse a 2-space indent.\n",
+        )
+        .unwrap();
+
+        let mut file = MemoryFile::from_content(
+            dir.path().join("CLAUDE.md"),
+            MemoryType::ProjectMemory,
+            "Indent with tabs.\n@docs/style.md\n",
+        );
+        let mut resolver = ImportResolver::new(dir.path());
+        resolver.resolve_imports(&mut file).unwrap();
+        let context = resolver.expand(&file, MAX_IMPORT_DEPTH).unwrap();
+
+        // Not found without following the import
+        assert!(ContradictionDetector::new()
+            .find(std::slice::from_ref(&file))
+            .is_empty());
+
+        let found = ContradictionDetector::new().find_expanded(&[(&file, context)]);
+        assert_eq!(found.len(), 1);
+        let (winner, overridden) = (&found[0].winner, &found[0].overridden);
+        assert!(winner.file.ends_with("docs/style.md"));
+        assert_eq!((winner.line, winner.choice.as_str()), (2, "2 spaces"));
+        assert_eq!(overridden.file, file.path);
+        assert_eq!((overridden.line, overridden.choice.as_str()), (1, "tabs"));
+    }
+}
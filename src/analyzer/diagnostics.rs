@@ -1,9 +1,25 @@
+use super::fix::{line_bounds, Fix, TextEdit};
+use super::rules::suppressions::UNUSED_SUPPRESSION;
+use super::rules::{Rule, RuleContext, RuleRegistry};
+use super::suppression::{Suppression, SuppressionScope, Suppressions};
 use super::{Diagnostic, DiagnosticLevel, DiagnosticResult, PerformanceMetrics};
-use crate::core::MemoryFile;
+use crate::core::parser::ParsedContent;
+use crate::core::{Config, ImportGraph, MemoryFile, Parser, ShardedCache};
 use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 pub struct Diagnostics {
-    strict: bool,
+    all_rules: bool,
+    warnings_as_errors: bool,
+    config: Config,
+    project_root: PathBuf,
+    registry: RuleRegistry,
+    enabled: HashSet<String>,
+    disabled: HashSet<String>,
+    severity: HashMap<String, DiagnosticLevel>,
+    parser: Parser,
 }
 
 impl Default for Diagnostics {
@@ -14,58 +30,384 @@ impl Default for Diagnostics {
 
 impl Diagnostics {
     pub fn new() -> Self {
-        Self { strict: false }
+        Self {
+            all_rules: false,
+            warnings_as_errors: false,
+            config: Config::default(),
+            project_root: PathBuf::from("."),
+            registry: RuleRegistry::builtin(),
+            enabled: HashSet::new(),
+            disabled: HashSet::new(),
+            severity: HashMap::new(),
+            parser: Parser::new(),
+        }
     }
 
-    pub fn with_strict(mut self, strict: bool) -> Self {
-        self.strict = strict;
+    /// Runs opt-in rules too.
+    pub fn with_all_rules(mut self, all_rules: bool) -> Self {
+        self.all_rules = all_rules;
         self
     }
 
-    pub fn check(&self, files: &[MemoryFile]) -> Result<DiagnosticResult> {
-        let mut errors = Vec::new();
-        let mut warnings = Vec::new();
-        let suggestions = Vec::new();
-
-        // Basic checks
-        for file in files {
-            // Check file size
-            if file.metadata.size > 1_000_000 {
-                warnings.push(Diagnostic {
-                    level: DiagnosticLevel::Warning,
-                    message: format!("File is larger than 1MB: {}", file.path.display()),
-                    file: Some(file.path.to_string_lossy().to_string()),
-                    line: None,
-                    code: "large-file".to_string(),
-                });
-            }
+    /// Reports warnings as errors.
+    pub fn with_warnings_as_errors(mut self, warnings_as_errors: bool) -> Self {
+        self.warnings_as_errors = warnings_as_errors;
+        self
+    }
 
-            // Check for missing imports
-            for import in &file.imports {
-                if import.resolved_path.is_none() {
-                    errors.push(Diagnostic {
-                        level: DiagnosticLevel::Error,
-                        message: format!("Unresolved import: {}", import.path),
-                        file: Some(file.path.to_string_lossy().to_string()),
-                        line: Some(import.line_number),
-                        code: "missing-import".to_string(),
-                    });
-                }
-            }
+    pub fn with_config(mut self, config: Config) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn with_project_root<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.project_root = path.as_ref().to_path_buf();
+        self
+    }
+
+    /// Shares parse results between rules, and between runs over the same files.
+    pub fn with_parse_cache(mut self, cache: Arc<ShardedCache<ParsedContent>>) -> Self {
+        self.parser = Parser::new().with_cache(cache);
+        self
+    }
+
+    pub fn with_registry(mut self, registry: RuleRegistry) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    /// Rules to run even if they are opt-in or disabled in the config.
+    pub fn with_enabled_rules<I, S>(mut self, ids: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.enabled.extend(ids.into_iter().map(Into::into));
+        self
+    }
+
+    /// Rules to skip; takes precedence over everything else.
+    pub fn with_disabled_rules<I, S>(mut self, ids: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.disabled.extend(ids.into_iter().map(Into::into));
+        self
+    }
+
+    /// Overrides the severity of every diagnostic produced by `id`.
+    pub fn with_severity(mut self, id: impl Into<String>, level: DiagnosticLevel) -> Self {
+        self.severity.insert(id.into(), level);
+        self
+    }
+
+    pub fn registry(&self) -> &RuleRegistry {
+        &self.registry
+    }
+
+    /// Whether `rule` runs, with CLI choices taking precedence over the config.
+    pub fn is_enabled(&self, rule: &dyn Rule) -> bool {
+        let id = rule.id();
+        let settings = &self.config.diagnostics;
+
+        if self.disabled.contains(id) {
+            false
+        } else if self.enabled.contains(id) {
+            true
+        } else if settings.disable.iter().any(|d| d == id) {
+            false
+        } else {
+            settings.enable.iter().any(|e| e == id)
+                || self.all_rules
+                || settings.all_rules
+                || settings.strict
+                || rule.enabled_by_default()
         }
+    }
 
+    /// Severity diagnostics of `rule` are reported at.
+    pub fn effective_level(&self, rule: &dyn Rule) -> Option<DiagnosticLevel> {
+        self.severity
+            .get(rule.id())
+            .or_else(|| self.config.diagnostics.severity.get(rule.id()))
+            .copied()
+    }
+
+    pub fn check(&self, files: &[MemoryFile]) -> Result<DiagnosticResult> {
+        let graph = ImportGraph::from_files(files);
+        self.check_with_graph(files, &graph)
+    }
+
+    /// Runs the checks against an import graph built by the caller, e.g. one
+    /// that follows imports transitively via `ImportResolver::build_graph`.
+    pub fn check_with_graph(
+        &self,
+        files: &[MemoryFile],
+        graph: &ImportGraph,
+    ) -> Result<DiagnosticResult> {
+        let ctx = RuleContext {
+            files,
+            graph,
+            config: &self.config,
+            project_root: &self.project_root,
+            parser: &self.parser,
+        };
+
+        let import_chains: Vec<Vec<PathBuf>> = files
+            .iter()
+            .filter_map(|file| {
+                let path = file
+                    .path
+                    .canonicalize()
+                    .unwrap_or_else(|_| file.path.clone());
+                graph
+                    .shortest_chains(&path)
+                    .into_values()
+                    .max_by_key(Vec::len)
+                    .filter(|chain| chain.len() > 1)
+            })
+            .collect();
         let metrics = PerformanceMetrics {
             total_files: files.len(),
             total_size: files.iter().map(|f| f.metadata.size).sum(),
-            import_depth: 0,     // TODO: Calculate actual import depth
-            circular_imports: 0, // TODO: Detect circular imports
+            import_depth: import_chains
+                .iter()
+                .map(|chain| chain.len() - 1)
+                .max()
+                .unwrap_or(0),
+            import_chains,
+            circular_imports: graph.cycles().len(),
         };
 
-        Ok(DiagnosticResult {
-            errors,
-            warnings,
-            suggestions,
+        let mut result = DiagnosticResult {
+            errors: Vec::new(),
+            warnings: Vec::new(),
+            infos: Vec::new(),
+            suggestions: Vec::new(),
             metrics,
+            baseline: None,
+        };
+
+        let mut suppressions = Suppressions::from_files(files);
+        let mut ran = HashSet::new();
+
+        for rule in self.registry.rules().filter(|rule| self.is_enabled(*rule)) {
+            ran.insert(rule.id());
+            let level = self.effective_level(rule);
+            for mut diagnostic in rule.check(&ctx) {
+                if suppressions.suppress(&diagnostic) {
+                    continue;
+                }
+                if let Some(level) = level {
+                    diagnostic.level = level;
+                }
+                result.push(self.promote(diagnostic));
+            }
+        }
+
+        if let Some(rule) = self
+            .registry
+            .get(UNUSED_SUPPRESSION)
+            .filter(|rule| self.is_enabled(*rule))
+        {
+            let level = self.effective_level(rule).unwrap_or(rule.default_level());
+            for (path, suppression) in suppressions.unused() {
+                if let Some(diagnostic) = self.unused_suppression(path, suppression, files, &ran) {
+                    result.push(self.promote(Diagnostic {
+                        level,
+                        ..diagnostic
+                    }));
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    fn promote(&self, mut diagnostic: Diagnostic) -> Diagnostic {
+        let settings = &self.config.diagnostics;
+        if (self.warnings_as_errors || settings.warnings_as_errors || settings.strict)
+            && diagnostic.level == DiagnosticLevel::Warning
+        {
+            diagnostic.level = DiagnosticLevel::Error;
+        }
+        diagnostic
+    }
+
+    /// Reports a suppression that silenced nothing, unless the rules it names
+    /// did not run. Unknown rule ids are always reported.
+    fn unused_suppression(
+        &self,
+        path: &Path,
+        suppression: &Suppression,
+        files: &[MemoryFile],
+        ran: &HashSet<&str>,
+    ) -> Option<Diagnostic> {
+        let unknown: Vec<&str> = suppression
+            .rules
+            .iter()
+            .map(String::as_str)
+            .filter(|id| !self.registry.contains(id))
+            .collect();
+
+        let message = if !unknown.is_empty() {
+            format!("Suppression names unknown rule: {}", unknown.join(", "))
+        } else if suppression.rules.is_empty() {
+            "Unused suppression".to_string()
+        } else if suppression.rules.iter().any(|id| ran.contains(id.as_str())) {
+            format!("Unused suppression for {}", suppression.rules.join(", "))
+        } else {
+            return None;
+        };
+
+        let diagnostic = Diagnostic::new(DiagnosticLevel::Warning, UNUSED_SUPPRESSION, message)
+            .with_file(path)
+            .with_line(suppression.line);
+
+        // Only comment directives that fill their line can be removed safely
+        let file = files.iter().find(|f| f.path == path)?;
+        let removable = suppression.scope != SuppressionScope::File
+            && line_bounds(&file.content, suppression.line).is_some_and(|(start, end)| {
+                let line = file.content[start..end].trim();
+                line.starts_with("<!--") && line.ends_with("-->")
+            });
+        Some(match line_bounds(&file.content, suppression.line) {
+            Some((start, end)) if removable => diagnostic.with_fix(
+                Fix::new("Remove unused suppression").with_edit(TextEdit::delete(path, start, end)),
+            ),
+            _ => diagnostic,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::MemoryType;
+
+    struct OptIn;
+
+    impl Rule for OptIn {
+        fn id(&self) -> &str {
+            "opt-in"
+        }
+
+        fn default_level(&self) -> DiagnosticLevel {
+            DiagnosticLevel::Warning
+        }
+
+        fn description(&self) -> &str {
+            "Always reports"
+        }
+
+        fn enabled_by_default(&self) -> bool {
+            false
+        }
+
+        fn check(&self, _ctx: &RuleContext) -> Vec<Diagnostic> {
+            vec![Diagnostic::new(self.default_level(), self.id(), "found")]
+        }
+    }
+
+    fn levels(diagnostics: Diagnostics) -> Vec<DiagnosticLevel> {
+        let mut registry = RuleRegistry::new();
+        registry.register(Box::new(OptIn));
+        let files = [MemoryFile::from_content(
+            "/repo/CLAUDE.md",
+            MemoryType::ProjectMemory,
+            "",
+        )];
+        diagnostics
+            .with_registry(registry)
+            .check(&files)
+            .unwrap()
+            .diagnostics()
+            .map(|d| d.level)
+            .collect()
+    }
+
+    #[test]
+    fn all_rules_and_warnings_as_errors_are_independent() {
+        assert!(levels(Diagnostics::new()).is_empty());
+        assert!(levels(Diagnostics::new().with_warnings_as_errors(true)).is_empty());
+        assert_eq!(
+            levels(Diagnostics::new().with_all_rules(true)),
+            vec![DiagnosticLevel::Warning]
+        );
+        assert_eq!(
+            levels(
+                Diagnostics::new()
+                    .with_all_rules(true)
+                    .with_warnings_as_errors(true)
+            ),
+            vec![DiagnosticLevel::Error]
+        );
+
+        let mut config = Config::default();
+        config.diagnostics.strict = true;
+        assert_eq!(
+            levels(Diagnostics::new().with_config(config)),
+            vec![DiagnosticLevel::Error]
+        );
+    }
+
+    #[test]
+    fn style_rules_only_run_when_enabled() {
+        let files = [MemoryFile::from_content(
+            "/repo/CLAUDE.md",
+            MemoryType::ProjectMemory,
+            "# Title \n\n## Empty\n",
+        )];
+        let codes = |diagnostics: Diagnostics| -> Vec<String> {
+            let result = diagnostics.check(&files).unwrap();
+            let mut codes: Vec<String> = result.diagnostics().map(|d| d.code.clone()).collect();
+            codes.sort();
+            codes
+        };
+
+        assert!(codes(Diagnostics::new()).is_empty());
+        assert_eq!(
+            codes(Diagnostics::new().with_enabled_rules(["trailing-whitespace"])),
+            vec!["trailing-whitespace"]
+        );
+        assert_eq!(
+            codes(Diagnostics::new().with_all_rules(true)),
+            vec!["empty-section", "trailing-whitespace"]
+        );
+    }
+
+    #[test]
+    fn import_chains_are_reported_per_file() {
+        let files = [
+            MemoryFile::from_content("/repo/CLAUDE.md", MemoryType::ProjectMemory, ""),
+            MemoryFile::from_content("/repo/api/CLAUDE.md", MemoryType::SubdirMemory, ""),
+            MemoryFile::from_content("/repo/web/CLAUDE.md", MemoryType::SubdirMemory, ""),
+        ];
+        let mut graph = ImportGraph::from_files(&files);
+        graph.add_edge(Path::new("/repo/CLAUDE.md"), Path::new("/repo/a.md"), 1);
+        graph.add_edge(Path::new("/repo/api/CLAUDE.md"), Path::new("/repo/b.md"), 1);
+        graph.add_edge(Path::new("/repo/b.md"), Path::new("/repo/c.md"), 1);
+
+        let metrics = Diagnostics::new()
+            .with_registry(RuleRegistry::new())
+            .check_with_graph(&files, &graph)
+            .unwrap()
+            .metrics;
+        assert_eq!(metrics.import_depth, 2);
+        assert_eq!(
+            metrics.import_chains,
+            vec![
+                vec![
+                    PathBuf::from("/repo/CLAUDE.md"),
+                    PathBuf::from("/repo/a.md")
+                ],
+                vec![
+                    PathBuf::from("/repo/api/CLAUDE.md"),
+                    PathBuf::from("/repo/b.md"),
+                    PathBuf::from("/repo/c.md"),
+                ],
+            ]
+        );
+    }
+}
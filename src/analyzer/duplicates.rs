@@ -0,0 +1,329 @@
+use crate::core::{MemoryFile, MemoryType};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+const SHINGLE_SIZE: usize = 3;
+
+static LIST_ITEM_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\s*(?:[-*+]|\d+[.)])\s+(.*)$").unwrap());
+
+/// A paragraph or list item of a memory file.
+#[derive(Debug, Clone)]
+pub struct Block {
+    pub file: PathBuf,
+    pub line: usize,
+    pub text: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct Duplicate {
+    /// The copy in the more general memory file.
+    pub original: Block,
+    pub duplicate: Block,
+    /// Jaccard similarity of the word shingles of both blocks.
+    pub similarity: f64,
+    pub exact: bool,
+    /// Nearest memory file that applies to both locations.
+    pub hoist_to: PathBuf,
+}
+
+pub struct DuplicateDetector {
+    threshold: f64,
+    min_words: usize,
+}
+
+impl Default for DuplicateDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DuplicateDetector {
+    pub fn new() -> Self {
+        Self {
+            threshold: 0.6,
+            min_words: 5,
+        }
+    }
+
+    pub fn with_threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Blocks with fewer words are too short to compare meaningfully.
+    pub fn with_min_words(mut self, min_words: usize) -> Self {
+        self.min_words = min_words;
+        self
+    }
+
+    /// Finds exact and near-duplicate blocks. Files are visited from the most
+    /// general to the most specific memory, and each repeated block is
+    /// reported once, against its closest match seen before it.
+    pub fn find(&self, files: &[MemoryFile]) -> Vec<Duplicate> {
+        let mut ordered: Vec<&MemoryFile> = files.iter().collect();
+        ordered.sort_by_key(|file| file.precedence());
+
+        let mut seen: Vec<(Block, Vec<String>, HashSet<u64>)> = Vec::new();
+        let mut index: HashMap<u64, Vec<usize>> = HashMap::new();
+        let mut duplicates = Vec::new();
+
+        for file in ordered {
+            for block in blocks(file) {
+                let words = normalize(&block.text);
+                if words.len() < self.min_words {
+                    continue;
+                }
+                let shingles = shingles(&words);
+
+                let mut shared: HashMap<usize, usize> = HashMap::new();
+                for shingle in &shingles {
+                    for &id in index.get(shingle).into_iter().flatten() {
+                        *shared.entry(id).or_default() += 1;
+                    }
+                }
+
+                let best = shared
+                    .into_iter()
+                    .map(|(id, count)| {
+                        let union = shingles.len() + seen[id].2.len() - count;
+                        (id, count as f64 / union as f64)
+                    })
+                    .filter(|(_, similarity)| *similarity >= self.threshold)
+                    .max_by(|a, b| a.1.total_cmp(&b.1).then(b.0.cmp(&a.0)));
+
+                if let Some((id, similarity)) = best {
+                    let (original, original_words, _) = &seen[id];
+                    duplicates.push(Duplicate {
+                        hoist_to: hoist_target(files, &original.file, &block.file),
+                        original: original.clone(),
+                        duplicate: block.clone(),
+                        similarity,
+                        exact: *original_words == words,
+                    });
+                }
+
+                for shingle in &shingles {
+                    index.entry(*shingle).or_default().push(seen.len());
+                }
+                seen.push((block, words, shingles));
+            }
+        }
+
+        duplicates
+    }
+}
+
+/// Splits a memory file into paragraphs and list items, skipping headings,
+/// code blocks, tables, comments and import lines.
+pub fn blocks(file: &MemoryFile) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut current: Option<Block> = None;
+    let mut in_code_block = false;
+
+    for (idx, line) in file.content.lines().enumerate() {
+        let trimmed = line.trim();
+
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code_block = !in_code_block;
+            blocks.extend(current.take());
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+
+        let is_import_line = trimmed.starts_with("{{import")
+            || (trimmed.starts_with('@') && !trimmed.contains(char::is_whitespace));
+        if trimmed.is_empty()
+            || trimmed.starts_with('#')
+            || trimmed.starts_with('|')
+            || trimmed.starts_with("<!--")
+            || is_import_line
+        {
+            blocks.extend(current.take());
+            continue;
+        }
+
+        if let Some(captures) = LIST_ITEM_REGEX.captures(line) {
+            blocks.extend(current.take());
+            current = Some(Block {
+                file: file.path.clone(),
+                line: idx + 1,
+                text: captures[1].to_string(),
+            });
+        } else if let Some(block) = current.as_mut() {
+            block.text.push(' ');
+            block.text.push_str(trimmed);
+        } else {
+            current = Some(Block {
+                file: file.path.clone(),
+                line: idx + 1,
+                text: trimmed.to_string(),
+            });
+        }
+    }
+    blocks.extend(current);
+
+    blocks
+}
+
+fn normalize(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+fn shingles(words: &[String]) -> HashSet<u64> {
+    words
+        .windows(SHINGLE_SIZE.min(words.len()).max(1))
+        .map(|window| {
+            let mut hasher = DefaultHasher::new();
+            window.hash(&mut hasher);
+            hasher.finish()
+        })
+        .collect()
+}
+
+/// The most specific memory file whose scope covers both `a` and `b`: the
+/// user memory if either is the user memory, otherwise the closest
+/// `CLAUDE.md` in a common ancestor directory. Falls back to a (possibly
+/// new) `CLAUDE.md` in the common directory.
+fn hoist_target(files: &[MemoryFile], a: &Path, b: &Path) -> PathBuf {
+    if let Some(user) = files
+        .iter()
+        .find(|f| f.file_type == MemoryType::UserMemory && (f.path == a || f.path == b))
+    {
+        return user.path.clone();
+    }
+
+    let common: PathBuf = a
+        .parent()
+        .unwrap_or(a)
+        .components()
+        .zip(b.parent().unwrap_or(b).components())
+        .take_while(|(x, y)| x == y)
+        .map(|(x, _)| x)
+        .collect();
+
+    files
+        .iter()
+        .filter(|f| {
+            matches!(
+                f.file_type,
+                MemoryType::ProjectMemory | MemoryType::SubdirMemory
+            )
+        })
+        .filter_map(|f| Some((f, f.path.parent()?)))
+        .filter(|(_, dir)| common.starts_with(dir))
+        .max_by_key(|(_, dir)| dir.components().count())
+        .map(|(f, _)| f.path.clone())
+        .unwrap_or_else(|| common.join("CLAUDE.md"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_paragraphs_and_list_items() {
+        let file = MemoryFile::from_content(
+            "/repo/CLAUDE.md",
+            MemoryType::ProjectMemory,
+            "# Title\nFirst line\ncontinues here\n\n- item one\n  wrapped\n1. item two\n\
+             ```\ncode\n```\n| table |\n@docs/guide.md\n<!-- comment -->\nLast\n",
+        );
+        let blocks: Vec<(usize, String)> = blocks(&file)
+            .into_iter()
+            .map(|b| (b.line, b.text))
+            .collect();
+        assert_eq!(
+            blocks,
+            vec![
+                (2, "First line continues here".to_string()),
+                (5, "item one wrapped".to_string()),
+                (7, "item two".to_string()),
+                (14, "Last".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_copies_against_the_more_general_file() {
+        let rule = "Always run the full test suite before pushing any change.";
+        let files = [
+            MemoryFile::from_content(
+                "/repo/api/CLAUDE.md",
+                MemoryType::SubdirMemory,
+                &format!("# API\n{}\n", rule),
+            ),
+            MemoryFile::from_content(
+                "/repo/web/CLAUDE.md",
+                MemoryType::SubdirMemory,
+                "- Always run the full test suite before pushing a change!\n- Short item here.\n",
+            ),
+            MemoryFile::from_content(
+                "/repo/CLAUDE.md",
+                MemoryType::ProjectMemory,
+                &format!("{}\n\nShort item here.\n", rule),
+            ),
+        ];
+
+        let duplicates = DuplicateDetector::new().find(&files);
+        let found: Vec<(&Path, &Path, bool)> = duplicates
+            .iter()
+            .map(|d| {
+                (
+                    d.original.file.as_path(),
+                    d.duplicate.file.as_path(),
+                    d.exact,
+                )
+            })
+            .collect();
+        // Blocks under `min_words` are not compared
+        assert_eq!(
+            found,
+            vec![
+                (
+                    Path::new("/repo/CLAUDE.md"),
+                    Path::new("/repo/api/CLAUDE.md"),
+                    true
+                ),
+                (
+                    Path::new("/repo/CLAUDE.md"),
+                    Path::new("/repo/web/CLAUDE.md"),
+                    false
+                ),
+            ]
+        );
+        assert!(duplicates[1].similarity >= 0.6 && duplicates[1].similarity < 1.0);
+        assert!(duplicates
+            .iter()
+            .all(|d| d.hoist_to == Path::new("/repo/CLAUDE.md")));
+    }
+
+    #[test]
+    fn hoists_to_the_nearest_common_memory_file() {
+        let rule = "Prefer small focused commits with descriptive messages.";
+        let files = [
+            MemoryFile::from_content("/repo/apps/api/CLAUDE.md", MemoryType::SubdirMemory, rule),
+            MemoryFile::from_content("/repo/apps/web/CLAUDE.md", MemoryType::SubdirMemory, rule),
+            MemoryFile::from_content("/repo/CLAUDE.md", MemoryType::ProjectMemory, "# Repo\n"),
+        ];
+
+        let duplicates = DuplicateDetector::new().find(&files);
+        assert_eq!(duplicates.len(), 1);
+        // No CLAUDE.md in `apps` yet, so the nearest existing one is used
+        assert_eq!(duplicates[0].hoist_to, Path::new("/repo/CLAUDE.md"));
+        assert!(DuplicateDetector::new()
+            .with_min_words(10)
+            .find(&files)
+            .is_empty());
+    }
+}
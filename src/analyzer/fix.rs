@@ -0,0 +1,429 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use similar::TextDiff;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Replaces the byte range `start..end` of `file` with `replacement`.
+/// Editing a file that does not exist creates it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextEdit {
+    pub file: PathBuf,
+    pub start: usize,
+    pub end: usize,
+    pub replacement: String,
+}
+
+impl TextEdit {
+    pub fn replace<P: AsRef<Path>>(
+        file: P,
+        start: usize,
+        end: usize,
+        replacement: impl Into<String>,
+    ) -> Self {
+        Self {
+            file: file.as_ref().to_path_buf(),
+            start,
+            end,
+            replacement: replacement.into(),
+        }
+    }
+
+    pub fn insert<P: AsRef<Path>>(file: P, offset: usize, text: impl Into<String>) -> Self {
+        Self::replace(file, offset, offset, text)
+    }
+
+    pub fn delete<P: AsRef<Path>>(file: P, start: usize, end: usize) -> Self {
+        Self::replace(file, start, end, "")
+    }
+
+    /// Two edits overlap if their ranges intersect or both insert at the
+    /// same offset, where the resulting order would be ambiguous.
+    fn overlaps(&self, other: &TextEdit) -> bool {
+        self.file == other.file
+            && ((self.start < other.end && other.start < self.end) || self.start == other.start)
+    }
+}
+
+/// A set of edits that resolves one diagnostic. Fixes are applied whole or
+/// not at all.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Fix {
+    pub description: String,
+    pub edits: Vec<TextEdit>,
+    /// Files removed once the edits are applied.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub delete: Vec<PathBuf>,
+}
+
+impl Fix {
+    pub fn new(description: impl Into<String>) -> Self {
+        Self {
+            description: description.into(),
+            edits: Vec::new(),
+            delete: Vec::new(),
+        }
+    }
+
+    pub fn with_edit(mut self, edit: TextEdit) -> Self {
+        self.edits.push(edit);
+        self
+    }
+
+    pub fn with_delete<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.delete.push(path.as_ref().to_path_buf());
+        self
+    }
+
+    fn conflicts(&self, other: &Fix) -> bool {
+        let touches = |fix: &Fix, path: &PathBuf| fix.edits.iter().any(|e| &e.file == path);
+
+        self.edits
+            .iter()
+            .any(|edit| other.edits.iter().any(|o| edit.overlaps(o)))
+            || self.delete.iter().any(|path| touches(other, path))
+            || other.delete.iter().any(|path| touches(self, path))
+    }
+}
+
+/// Byte range of the 1-based `line` in `content`, including its line break.
+pub fn line_bounds(content: &str, line: usize) -> Option<(usize, usize)> {
+    let mut start = 0;
+    for (idx, text) in content.split_inclusive('\n').enumerate() {
+        if idx + 1 == line {
+            return Some((start, start + text.len()));
+        }
+        start += text.len();
+    }
+    None
+}
+
+#[derive(Default)]
+pub struct FixEngine {
+    fixes: Vec<Fix>,
+}
+
+impl FixEngine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, fix: Fix) {
+        self.fixes.push(fix);
+    }
+
+    /// Accepts fixes in order, skipping any that overlaps an accepted fix or
+    /// no longer fits the file, and computes the resulting file contents.
+    pub fn plan(&self) -> Result<FixPlan> {
+        let mut originals: BTreeMap<PathBuf, Option<String>> = BTreeMap::new();
+        let mut applied: Vec<Fix> = Vec::new();
+        let mut skipped = Vec::new();
+
+        for fix in &self.fixes {
+            for path in fix.edits.iter().map(|e| &e.file).chain(&fix.delete) {
+                if !originals.contains_key(path) {
+                    originals.insert(path.clone(), read_existing(path)?);
+                }
+            }
+
+            let fits = fix.edits.iter().all(|edit| {
+                let content = originals[&edit.file].as_deref().unwrap_or_default();
+                edit.start <= edit.end
+                    && edit.end <= content.len()
+                    && content.is_char_boundary(edit.start)
+                    && content.is_char_boundary(edit.end)
+            });
+
+            if fits && !applied.iter().any(|other| fix.conflicts(other)) {
+                applied.push(fix.clone());
+            } else {
+                skipped.push(fix.clone());
+            }
+        }
+
+        let mut changes = BTreeMap::new();
+        for (path, original) in originals {
+            let deleted = applied.iter().any(|fix| fix.delete.contains(&path));
+            let mut edits: Vec<&TextEdit> = applied
+                .iter()
+                .flat_map(|fix| &fix.edits)
+                .filter(|edit| edit.file == path)
+                .collect();
+            edits.sort_by_key(|edit| std::cmp::Reverse(edit.start));
+
+            let updated = if deleted {
+                None
+            } else {
+                let mut content = original.clone().unwrap_or_default();
+                for edit in edits {
+                    content.replace_range(edit.start..edit.end, &edit.replacement);
+                }
+                Some(content)
+            };
+
+            if updated != original {
+                changes.insert(path, FileChange { original, updated });
+            }
+        }
+
+        Ok(FixPlan {
+            applied,
+            skipped,
+            changes,
+        })
+    }
+}
+
+struct FileChange {
+    original: Option<String>,
+    /// `None` when the file is deleted.
+    updated: Option<String>,
+}
+
+pub struct FixPlan {
+    pub applied: Vec<Fix>,
+    /// Fixes left out because they overlap an applied fix or are out of date.
+    pub skipped: Vec<Fix>,
+    changes: BTreeMap<PathBuf, FileChange>,
+}
+
+impl FixPlan {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Files that would be created, modified or deleted.
+    pub fn files(&self) -> impl Iterator<Item = &Path> {
+        self.changes.keys().map(|path| path.as_path())
+    }
+
+    /// Unified diff of every change.
+    pub fn diff(&self) -> String {
+        let mut output = String::new();
+
+        for (path, change) in &self.changes {
+            let label = |prefix: &str| {
+                let path = path.strip_prefix(".").unwrap_or(path);
+                if path.is_absolute() {
+                    path.display().to_string()
+                } else {
+                    format!("{}/{}", prefix, path.display())
+                }
+            };
+            let old_label = match change.original {
+                Some(_) => label("a"),
+                None => "/dev/null".to_string(),
+            };
+            let new_label = match change.updated {
+                Some(_) => label("b"),
+                None => "/dev/null".to_string(),
+            };
+            let old = change.original.as_deref().unwrap_or_default();
+            let new = change.updated.as_deref().unwrap_or_default();
+
+            output.push_str(
+                &TextDiff::from_lines(old, new)
+                    .unified_diff()
+                    .context_radius(3)
+                    .header(&old_label, &new_label)
+                    .to_string(),
+            );
+        }
+
+        output
+    }
+
+    /// Writes every change. New contents are staged next to their targets
+    /// first; if any write fails, files already replaced are restored.
+    pub fn apply(&self) -> Result<()> {
+        let mut staged: Vec<(&Path, PathBuf)> = Vec::new();
+
+        for (path, change) in &self.changes {
+            let Some(updated) = &change.updated else {
+                continue;
+            };
+            let result = stage(path, updated);
+            match result {
+                Ok(temp) => staged.push((path, temp)),
+                Err(err) => {
+                    for (_, temp) in &staged {
+                        let _ = fs::remove_file(temp);
+                    }
+                    return Err(err);
+                }
+            }
+        }
+
+        let mut replaced: Vec<&Path> = Vec::new();
+        for (idx, (path, temp)) in staged.iter().enumerate() {
+            if let Err(err) = fs::rename(temp, path) {
+                self.restore(&replaced);
+                for (_, temp) in &staged[idx..] {
+                    let _ = fs::remove_file(temp);
+                }
+                return Err(err).with_context(|| format!("Failed to write {}", path.display()));
+            }
+            replaced.push(path);
+        }
+
+        for (path, change) in &self.changes {
+            if change.updated.is_none() {
+                fs::remove_file(path)
+                    .with_context(|| format!("Failed to delete {}", path.display()))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn restore(&self, paths: &[&Path]) {
+        for path in paths {
+            match &self.changes[*path].original {
+                Some(original) => {
+                    let _ = fs::write(path, original);
+                }
+                None => {
+                    let _ = fs::remove_file(path);
+                }
+            }
+        }
+    }
+}
+
+fn read_existing(path: &Path) -> Result<Option<String>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    fs::read_to_string(path)
+        .map(Some)
+        .with_context(|| format!("Failed to read {}", path.display()))
+}
+
+fn stage(path: &Path, content: &str) -> Result<PathBuf> {
+    let dir = path.parent().unwrap_or(Path::new(""));
+    if !dir.as_os_str().is_empty() {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create directory {}", dir.display()))?;
+    }
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let temp = dir.join(format!(".{}.ccat-fix", name));
+    fs::write(&temp, content).with_context(|| format!("Failed to write {}", temp.display()))?;
+    Ok(temp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replace(path: &Path, start: usize, end: usize, text: &str) -> Fix {
+        Fix::new(text).with_edit(TextEdit::replace(path, start, end, text))
+    }
+
+    fn plan(fixes: Vec<Fix>) -> FixPlan {
+        let mut engine = FixEngine::new();
+        for fix in fixes {
+            engine.add(fix);
+        }
+        engine.plan().unwrap()
+    }
+
+    fn leftovers(dir: &Path) -> Vec<String> {
+        fs::read_dir(dir)
+            .unwrap()
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .filter(|name| name.ends_with(".ccat-fix"))
+            .collect()
+    }
+
+    #[test]
+    fn plan_skips_overlapping_and_stale_fixes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("CLAUDE.md");
+        fs::write(&path, "one two three").unwrap();
+
+        let plan = plan(vec![
+            replace(&path, 0, 3, "1"),
+            replace(&path, 2, 7, "overlap"),
+            replace(&path, 0, 0, "same offset"),
+            replace(&path, 8, 13, "3"),
+            replace(&path, 10, 20, "past the end"),
+            Fix::new("delete").with_delete(&path),
+        ]);
+
+        let applied: Vec<&str> = plan
+            .applied
+            .iter()
+            .map(|f| f.description.as_str())
+            .collect();
+        let skipped: Vec<&str> = plan
+            .skipped
+            .iter()
+            .map(|f| f.description.as_str())
+            .collect();
+        assert_eq!(applied, ["1", "3"]);
+        assert_eq!(
+            skipped,
+            ["overlap", "same offset", "past the end", "delete"]
+        );
+
+        plan.apply().unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1 two 3");
+        assert!(leftovers(dir.path()).is_empty());
+    }
+
+    #[test]
+    fn apply_creates_and_deletes_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let (old, new) = (dir.path().join("old.md"), dir.path().join("sub/new.md"));
+        fs::write(&old, "notes").unwrap();
+
+        let plan = plan(vec![Fix::new("move")
+            .with_edit(TextEdit::insert(&new, 0, "notes"))
+            .with_delete(&old)]);
+        assert_eq!(plan.files().count(), 2);
+
+        plan.apply().unwrap();
+        assert!(!old.exists());
+        assert_eq!(fs::read_to_string(&new).unwrap(), "notes");
+    }
+
+    #[test]
+    fn apply_restores_replaced_files_when_a_write_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let (first, second) = (dir.path().join("a.md"), dir.path().join("b.md"));
+        fs::write(&first, "first").unwrap();
+        fs::write(&second, "second").unwrap();
+
+        let plan = plan(vec![
+            replace(&first, 0, 5, "FIRST"),
+            replace(&second, 0, 6, "SECOND"),
+        ]);
+        // `a.md` is replaced before renaming onto `b.md` fails
+        fs::remove_file(&second).unwrap();
+        fs::create_dir(&second).unwrap();
+        fs::write(second.join("keep"), "").unwrap();
+
+        assert!(plan.apply().is_err());
+        assert_eq!(fs::read_to_string(&first).unwrap(), "first");
+        assert!(leftovers(dir.path()).is_empty());
+    }
+
+    #[test]
+    fn apply_writes_nothing_when_staging_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = dir.path().join("a.md");
+        fs::write(&first, "first").unwrap();
+        fs::write(dir.path().join("blocker"), "").unwrap();
+
+        let plan = plan(vec![
+            replace(&first, 0, 5, "FIRST"),
+            Fix::new("new").with_edit(TextEdit::insert(dir.path().join("blocker/new.md"), 0, "x")),
+        ]);
+
+        assert!(plan.apply().is_err());
+        assert_eq!(fs::read_to_string(&first).unwrap(), "first");
+        assert!(leftovers(dir.path()).is_empty());
+    }
+}
@@ -1,16 +1,31 @@
+pub mod baseline;
+pub mod context;
+pub mod contradictions;
 pub mod diagnostics;
+pub mod duplicates;
+pub mod fix;
 pub mod profiler;
+pub mod rules;
 pub mod security;
+pub mod staleness;
+pub mod suppression;
+pub mod tokens;
 pub mod validator;
 
 use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DiagnosticResult {
     pub errors: Vec<Diagnostic>,
     pub warnings: Vec<Diagnostic>,
+    #[serde(default)]
+    pub infos: Vec<Diagnostic>,
     pub suggestions: Vec<Suggestion>,
     pub metrics: PerformanceMetrics,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub baseline: Option<BaselineSummary>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,28 +34,148 @@ pub struct Diagnostic {
     pub message: String,
     pub file: Option<String>,
     pub line: Option<usize>,
+    /// 1-based column within `line`, where the rule knows it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub column: Option<usize>,
     pub code: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fix: Option<Fix>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl DiagnosticResult {
+    /// Files a diagnostic under errors, warnings or infos by its level.
+    pub fn push(&mut self, diagnostic: Diagnostic) {
+        match diagnostic.level {
+            DiagnosticLevel::Error => self.errors.push(diagnostic),
+            DiagnosticLevel::Warning => self.warnings.push(diagnostic),
+            DiagnosticLevel::Info => self.infos.push(diagnostic),
+        }
+    }
+
+    /// Number of diagnostics at `level` or more severe.
+    pub fn count_at_least(&self, level: DiagnosticLevel) -> usize {
+        self.diagnostics()
+            .filter(|d| d.level.rank() >= level.rank())
+            .count()
+    }
+
+    /// All diagnostics, most severe first.
+    pub fn diagnostics(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.errors
+            .iter()
+            .chain(self.warnings.iter())
+            .chain(self.infos.iter())
+    }
+}
+
+impl Diagnostic {
+    pub fn new(
+        level: DiagnosticLevel,
+        code: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            level,
+            message: message.into(),
+            file: None,
+            line: None,
+            column: None,
+            code: code.into(),
+            fix: None,
+        }
+    }
+
+    pub fn with_file<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.file = Some(path.as_ref().to_string_lossy().to_string());
+        self
+    }
+
+    pub fn with_line(mut self, line: usize) -> Self {
+        self.line = Some(line);
+        self
+    }
+
+    pub fn with_column(mut self, column: usize) -> Self {
+        self.column = Some(column);
+        self
+    }
+
+    pub fn with_fix(mut self, fix: Fix) -> Self {
+        self.fix = Some(fix);
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum DiagnosticLevel {
+    #[serde(alias = "error")]
     Error,
+    #[serde(alias = "warning")]
     Warning,
+    #[serde(alias = "info")]
     Info,
 }
 
+impl DiagnosticLevel {
+    /// Higher is more severe.
+    pub fn rank(self) -> u8 {
+        match self {
+            DiagnosticLevel::Error => 2,
+            DiagnosticLevel::Warning => 1,
+            DiagnosticLevel::Info => 0,
+        }
+    }
+}
+
+impl std::fmt::Display for DiagnosticLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DiagnosticLevel::Error => write!(f, "error"),
+            DiagnosticLevel::Warning => write!(f, "warning"),
+            DiagnosticLevel::Info => write!(f, "info"),
+        }
+    }
+}
+
+impl FromStr for DiagnosticLevel {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "error" => Ok(DiagnosticLevel::Error),
+            "warning" | "warn" => Ok(DiagnosticLevel::Warning),
+            "info" => Ok(DiagnosticLevel::Info),
+            _ => anyhow::bail!("Unknown severity: {} (expected error, warning or info)", s),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Suggestion {
     pub message: String,
-    pub fix: Option<String>,
+    pub fix: Option<Fix>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PerformanceMetrics {
     pub total_files: usize,
     pub total_size: u64,
+    /// Largest number of hops from any scanned file to a file it imports,
+    /// following the shortest import chain to each file.
     pub import_depth: usize,
+    /// For each scanned file that imports others, the shortest chain to its
+    /// most deeply imported file, starting at the scanned file.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub import_chains: Vec<Vec<PathBuf>>,
     pub circular_imports: usize,
 }
 
+pub use baseline::{Baseline, BaselineEntry, BaselineSummary};
+pub use context::{ContextAnalyzer, Contribution, EffectiveContext};
+pub use contradictions::{Contradiction, ContradictionDetector, Topic};
 pub use diagnostics::Diagnostics;
+pub use duplicates::{Duplicate, DuplicateDetector};
+pub use fix::{Fix, FixEngine, FixPlan, TextEdit};
+pub use rules::{Rule, RuleContext, RuleRegistry};
+pub use security::{Secret, SecurityScanner};
+pub use staleness::{StaleReference, StalenessChecker};
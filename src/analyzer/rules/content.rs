@@ -0,0 +1,309 @@
+use super::{Rule, RuleContext};
+use crate::analyzer::context::{ContextAnalyzer, Contribution};
+use crate::analyzer::contradictions::ContradictionDetector;
+use crate::analyzer::duplicates::DuplicateDetector;
+use crate::analyzer::staleness::StalenessChecker;
+use crate::analyzer::{Diagnostic, DiagnosticLevel};
+use crate::core::{ExpandedContext, ImportResolver, MemoryFile, MemoryType, MAX_IMPORT_DEPTH};
+use std::path::Path;
+
+// Contributors named in a context-budget message
+const TOP_CONTRIBUTORS: usize = 3;
+
+pub struct DuplicateInstruction;
+
+impl Rule for DuplicateInstruction {
+    fn id(&self) -> &str {
+        "duplicate-instruction"
+    }
+
+    fn default_level(&self) -> DiagnosticLevel {
+        DiagnosticLevel::Warning
+    }
+
+    fn description(&self) -> &str {
+        "Paragraph or list item repeats one from another memory file"
+    }
+
+    fn explanation(&self) -> &str {
+        "Paragraphs and list items are compared across all scanned memory files using word \
+         shingles, so copies with small edits are found too. The copy in the more specific \
+         file is reported, together with the nearest memory file that applies to both \
+         locations; keeping the instruction only there saves tokens in every conversation."
+    }
+
+    fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        DuplicateDetector::new()
+            .find(ctx.files)
+            .into_iter()
+            .map(|duplicate| {
+                let original = &duplicate.original;
+                let kind = if duplicate.exact {
+                    "Duplicate".to_string()
+                } else {
+                    format!(
+                        "Near-duplicate ({:.0}% similar)",
+                        duplicate.similarity * 100.0
+                    )
+                };
+
+                let message = if original.file == duplicate.duplicate.file {
+                    format!("{} of line {} in the same file", kind, original.line)
+                } else if duplicate.hoist_to == original.file {
+                    format!(
+                        "{} of {}:{}, which already applies here; remove this copy",
+                        kind,
+                        original.file.display(),
+                        original.line
+                    )
+                } else {
+                    format!(
+                        "{} of {}:{}; hoist it into {}",
+                        kind,
+                        original.file.display(),
+                        original.line,
+                        duplicate.hoist_to.display()
+                    )
+                };
+
+                Diagnostic::new(self.default_level(), self.id(), message)
+                    .with_file(&duplicate.duplicate.file)
+                    .with_line(duplicate.duplicate.line)
+            })
+            .collect()
+    }
+}
+
+pub struct ContradictoryInstruction;
+
+impl Rule for ContradictoryInstruction {
+    fn id(&self) -> &str {
+        "contradictory-instruction"
+    }
+
+    fn default_level(&self) -> DiagnosticLevel {
+        DiagnosticLevel::Warning
+    }
+
+    fn description(&self) -> &str {
+        "Memory files give opposing instructions on the same topic"
+    }
+
+    fn explanation(&self) -> &str {
+        "Instructions are matched against a table of opposing choices: indentation, quote \
+         style, package manager, test runner, programming and response language. When files \
+         whose scopes overlap pick different choices, the instruction in the more specific \
+         file takes precedence (user < project < local < subdirectory). Imported files count \
+         as part of the memory file that imports them, where later lines win. It is reported \
+         at the file and line it comes from, together with the instruction it overrides so \
+         one of them can be removed."
+    }
+
+    fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        let mut resolver = ImportResolver::from_config(ctx.project_root, ctx.config);
+        let expanded: Vec<(&MemoryFile, ExpandedContext)> = ctx
+            .files
+            .iter()
+            .map(|file| {
+                let context = resolver
+                    .expand(file, MAX_IMPORT_DEPTH)
+                    .unwrap_or_else(|_| ExpandedContext::unexpanded(file));
+                (file, context)
+            })
+            .collect();
+
+        ContradictionDetector::new()
+            .find_expanded(&expanded)
+            .into_iter()
+            .map(|contradiction| {
+                let winner = &contradiction.winner;
+                let overridden = &contradiction.overridden;
+
+                let message = if contradiction.same_file() {
+                    format!(
+                        "Contradicts line {} on {}: \"{}\" vs \"{}\"",
+                        overridden.line, contradiction.topic, winner.text, overridden.text
+                    )
+                } else {
+                    format!(
+                        "Contradicts {}:{} on {}: \"{}\" vs \"{}\"; {} takes precedence",
+                        overridden.file.display(),
+                        overridden.line,
+                        contradiction.topic,
+                        winner.text,
+                        overridden.text,
+                        winner.file.display()
+                    )
+                };
+
+                Diagnostic::new(self.default_level(), self.id(), message)
+                    .with_file(&winner.file)
+                    .with_line(winner.line)
+            })
+            .collect()
+    }
+}
+
+pub struct StaleReference;
+
+impl Rule for StaleReference {
+    fn id(&self) -> &str {
+        "stale-reference"
+    }
+
+    fn default_level(&self) -> DiagnosticLevel {
+        DiagnosticLevel::Warning
+    }
+
+    fn description(&self) -> &str {
+        "Mentioned path or build command no longer exists"
+    }
+
+    fn explanation(&self) -> &str {
+        "Relative paths (with a directory and either a trailing `/` or a file extension) are \
+         checked against the memory file's directory and the project root. In code blocks and \
+         inline code, `make` targets, `just` recipes, `npm`/`yarn`/`pnpm`/`bun run` scripts \
+         and `cargo -p`/`--bin`/`--example` names are checked against the Makefile, justfile, \
+         package.json and Cargo.toml. Build output directories such as `target/` are skipped."
+    }
+
+    fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        StalenessChecker::new(ctx.project_root)
+            .find(ctx.files)
+            .into_iter()
+            .map(|stale| {
+                Diagnostic::new(
+                    self.default_level(),
+                    self.id(),
+                    format!("Stale reference `{}`: {}", stale.reference, stale.reason),
+                )
+                .with_file(&stale.file)
+                .with_line(stale.line)
+            })
+            .collect()
+    }
+}
+
+pub struct ContextBudget;
+
+impl Rule for ContextBudget {
+    fn id(&self) -> &str {
+        "context-budget"
+    }
+
+    fn default_level(&self) -> DiagnosticLevel {
+        DiagnosticLevel::Warning
+    }
+
+    fn description(&self) -> &str {
+        "Context loaded in a directory exceeds the token budget"
+    }
+
+    fn explanation(&self) -> &str {
+        "For every directory with a memory file, the context Claude Code loads there is \
+         assembled: user memory, memory files of the directories above the project, the \
+         project and local memory and the subdirectory memories down to that directory, with \
+         all imports expanded. Its estimated token count is compared with \
+         `[diagnostics.context_budget]` (`warning_tokens`, default 10000, and `error_tokens`, \
+         default 25000). The largest files and sections are listed as candidates for trimming \
+         or moving into a subdirectory memory file. Directories whose context cannot be \
+         assembled are reported at info level."
+    }
+
+    fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        let budget = &ctx.config.diagnostics.context_budget;
+
+        // Report on the memory file of the directory itself, preferring the
+        // checked-in one over CLAUDE.local.md
+        let own_file = |directory: &Path| {
+            let own: Vec<_> = ctx
+                .files
+                .iter()
+                .filter(|f| f.path.parent() == Some(directory))
+                .collect();
+            own.iter()
+                .find(|f| f.file_type != MemoryType::LocalMemory)
+                .or(own.first())
+                .map(|f| f.path.clone())
+        };
+
+        ContextAnalyzer::new(ctx.project_root, ctx.config)
+            .analyze(ctx.files)
+            .into_iter()
+            .filter_map(|(directory, context)| {
+                let file = own_file(&directory)?;
+                let context = match context {
+                    Ok(context) => context,
+                    Err(err) => {
+                        let message = format!(
+                            "Context loaded in {} could not be computed: {}",
+                            display(&directory, ctx.project_root),
+                            err
+                        );
+                        return Some(
+                            Diagnostic::new(DiagnosticLevel::Info, self.id(), message)
+                                .with_file(file)
+                                .with_line(1),
+                        );
+                    }
+                };
+                if context.tokens <= budget.warning_tokens {
+                    return None;
+                }
+                let (level, limit) = if context.tokens > budget.error_tokens {
+                    (DiagnosticLevel::Error, budget.error_tokens)
+                } else {
+                    (self.default_level(), budget.warning_tokens)
+                };
+
+                let files = top(&context.files, ctx.project_root);
+                let sections = top(&context.sections, ctx.project_root);
+                let message = format!(
+                    "Context loaded in {} is ~{} tokens (limit {}); largest files: {}; largest sections: {}",
+                    display(&context.directory, ctx.project_root),
+                    context.tokens,
+                    limit,
+                    files,
+                    sections
+                );
+
+                Some(
+                    Diagnostic::new(level, self.id(), message)
+                        .with_file(file)
+                        .with_line(1),
+                )
+            })
+            .collect()
+    }
+}
+
+fn top(contributions: &[Contribution], project_root: &Path) -> String {
+    contributions
+        .iter()
+        .take(TOP_CONTRIBUTORS)
+        .map(|c| {
+            let file = display(&c.file, project_root);
+            match &c.section {
+                Some(section) => format!("\"{}\" in {}:{} ({})", section, file, c.line, c.tokens),
+                None => format!("{} ({})", file, c.tokens),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// `path` relative to the project root; imported files are resolved to
+/// absolute paths, so the canonical root is tried as well.
+fn display(path: &Path, project_root: &Path) -> String {
+    let canonical = project_root.canonicalize().ok();
+    let relative = path.strip_prefix(project_root).ok().or_else(|| {
+        canonical
+            .as_deref()
+            .and_then(|root| path.strip_prefix(root).ok())
+    });
+    match relative {
+        Some(relative) if relative.as_os_str().is_empty() => ".".to_string(),
+        Some(relative) => relative.display().to_string(),
+        None => path.display().to_string(),
+    }
+}
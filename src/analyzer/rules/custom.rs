@@ -0,0 +1,524 @@
+use super::markdown::lines;
+use super::{Rule, RuleContext};
+use crate::analyzer::fix::{Fix, TextEdit};
+use crate::analyzer::{Diagnostic, DiagnosticLevel};
+use crate::core::parser::Parser;
+use crate::core::{MemoryFile, MemoryType};
+use anyhow::{bail, Context, Result};
+use glob::Pattern;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+// Ids must survive being listed in suppression comments and --ignore
+static ID_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[A-Za-z0-9][A-Za-z0-9_./-]*$").unwrap());
+
+/// A team rule as written in a rule file or in `[[diagnostics.rules]]`.
+/// Exactly one of `pattern` and `require_heading` must be set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomRuleSpec {
+    pub id: String,
+    #[serde(default = "default_severity")]
+    pub severity: DiagnosticLevel,
+    /// Reported message; `{match}` is replaced with the matched text.
+    pub message: String,
+    /// Summary for `ccat rules list`; defaults to the message.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Regex reported on every match, checked line by line.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    /// Regex that at least one heading of each file in scope must match.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub require_heading: Option<String>,
+    /// Replaces each `pattern` match (`$1` expands to a capture group), or
+    /// is appended to files missing the required heading.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replacement: Option<String>,
+    #[serde(default)]
+    pub scope: RuleScope,
+    /// Only run with `--all-rules` or `--enable`.
+    #[serde(default)]
+    pub opt_in: bool,
+}
+
+fn default_severity() -> DiagnosticLevel {
+    DiagnosticLevel::Warning
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RuleScope {
+    /// Memory file types to check; empty checks all of them.
+    pub file_types: Vec<FileType>,
+    /// Glob patterns matched against paths relative to the project root.
+    pub paths: Vec<String>,
+    /// Only check lines under these headings, including subsections.
+    pub sections: Vec<String>,
+    pub code_blocks: CodeBlocks,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileType {
+    Project,
+    User,
+    Local,
+    Subdir,
+}
+
+impl FileType {
+    fn matches(self, file_type: &MemoryType) -> bool {
+        matches!(
+            (self, file_type),
+            (FileType::Project, MemoryType::ProjectMemory)
+                | (FileType::User, MemoryType::UserMemory)
+                | (FileType::Local, MemoryType::LocalMemory)
+                | (FileType::Subdir, MemoryType::SubdirMemory)
+        )
+    }
+}
+
+/// Whether `pattern` rules look inside fenced code blocks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CodeBlocks {
+    #[default]
+    Exclude,
+    Include,
+    Only,
+}
+
+/// Rules as stored in a rule file, under a top-level `rules` list.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleFile {
+    rules: Vec<CustomRuleSpec>,
+}
+
+/// Reads the rules of a `.toml`, `.yaml` or `.yml` rule file.
+pub fn load_file(path: &Path) -> Result<Vec<CustomRule>> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read rule file: {}", path.display()))?;
+    let file: RuleFile = match path.extension().and_then(|e| e.to_str()) {
+        Some("toml") => toml::from_str(&content).map_err(anyhow::Error::from),
+        Some("yaml" | "yml") => serde_yaml::from_str(&content).map_err(anyhow::Error::from),
+        _ => bail!(
+            "Unsupported rule file {} (expected .toml, .yaml or .yml)",
+            path.display()
+        ),
+    }
+    .with_context(|| format!("Invalid rule file: {}", path.display()))?;
+
+    file.rules
+        .into_iter()
+        .map(|spec| {
+            CustomRule::new(spec).with_context(|| format!("Invalid rule file: {}", path.display()))
+        })
+        .collect()
+}
+
+pub(super) fn check_id(id: &str) -> Result<()> {
+    if !ID_REGEX.is_match(id) {
+        bail!("Invalid custom rule id: {:?}", id);
+    }
+    Ok(())
+}
+
+enum Condition {
+    Pattern(Regex),
+    RequireHeading(Regex),
+}
+
+pub struct CustomRule {
+    spec: CustomRuleSpec,
+    condition: Condition,
+    paths: Vec<Pattern>,
+}
+
+impl CustomRule {
+    pub fn new(spec: CustomRuleSpec) -> Result<Self> {
+        check_id(&spec.id)?;
+
+        let compile = |pattern: &str| {
+            Regex::new(pattern).with_context(|| format!("Invalid regex in custom rule {}", spec.id))
+        };
+        let condition = match (&spec.pattern, &spec.require_heading) {
+            (Some(pattern), None) => Condition::Pattern(compile(pattern)?),
+            (None, Some(heading)) => Condition::RequireHeading(compile(heading)?),
+            _ => bail!(
+                "Custom rule {} needs exactly one of `pattern` or `require_heading`",
+                spec.id
+            ),
+        };
+
+        let paths = spec
+            .scope
+            .paths
+            .iter()
+            .map(|path| {
+                Pattern::new(path)
+                    .with_context(|| format!("Invalid path glob in custom rule {}", spec.id))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            spec,
+            condition,
+            paths,
+        })
+    }
+
+    fn in_scope(&self, file: &MemoryFile, project_root: &Path) -> bool {
+        let scope = &self.spec.scope;
+        let relative = file.path.strip_prefix(project_root).unwrap_or(&file.path);
+
+        (scope.file_types.is_empty() || scope.file_types.iter().any(|t| t.matches(&file.file_type)))
+            && (self.paths.is_empty() || self.paths.iter().any(|p| p.matches_path(relative)))
+    }
+
+    /// Line ranges of the configured sections, or `None` when the rule is
+    /// not limited to sections.
+    fn section_ranges(&self, file: &MemoryFile, parser: &Parser) -> Option<Vec<(usize, usize)>> {
+        if self.spec.scope.sections.is_empty() {
+            return None;
+        }
+        let parsed = parser.parse(file).ok()?;
+        Some(
+            self.spec
+                .scope
+                .sections
+                .iter()
+                .filter_map(|name| parsed.section_range(name))
+                .collect(),
+        )
+    }
+
+    fn check_pattern(&self, file: &MemoryFile, regex: &Regex, parser: &Parser) -> Vec<Diagnostic> {
+        let sections = self.section_ranges(file, parser);
+        let mut diagnostics = Vec::new();
+
+        for (offset, line_number, text, in_code) in lines(file) {
+            let in_code_scope = match self.spec.scope.code_blocks {
+                CodeBlocks::Exclude => !in_code,
+                CodeBlocks::Include => true,
+                CodeBlocks::Only => in_code,
+            };
+            let in_sections = sections.as_ref().is_none_or(|ranges| {
+                ranges
+                    .iter()
+                    .any(|(start, end)| (*start..=*end).contains(&line_number))
+            });
+            if !in_code_scope || !in_sections {
+                continue;
+            }
+
+            for captures in regex.captures_iter(text) {
+                let matched = captures.get(0).unwrap();
+                if matched.is_empty() {
+                    continue;
+                }
+
+                let mut diagnostic = Diagnostic::new(
+                    self.spec.severity,
+                    self.id(),
+                    self.spec.message.replace("{match}", matched.as_str()),
+                )
+                .with_file(&file.path)
+                .with_line(line_number)
+                .with_column(text[..matched.start()].chars().count() + 1);
+
+                if let Some(replacement) = &self.spec.replacement {
+                    let mut expanded = String::new();
+                    captures.expand(replacement, &mut expanded);
+                    diagnostic = diagnostic.with_fix(
+                        Fix::new(format!(
+                            "Replace `{}` with `{}`",
+                            matched.as_str(),
+                            expanded
+                        ))
+                        .with_edit(TextEdit::replace(
+                            &file.path,
+                            offset + matched.start(),
+                            offset + matched.end(),
+                            expanded,
+                        )),
+                    );
+                }
+                diagnostics.push(diagnostic);
+            }
+        }
+
+        diagnostics
+    }
+
+    fn check_heading(
+        &self,
+        file: &MemoryFile,
+        regex: &Regex,
+        parser: &Parser,
+    ) -> Option<Diagnostic> {
+        let parsed = parser.parse(file).ok()?;
+        let sections = self.section_ranges(file, parser);
+        let found = parsed.sections.iter().any(|section| {
+            regex.is_match(&section.title)
+                && sections.as_ref().is_none_or(|ranges| {
+                    ranges.iter().any(|(start, end)| {
+                        section.line_start > *start && section.line_start <= *end
+                    })
+                })
+        });
+        if found {
+            return None;
+        }
+
+        let diagnostic = Diagnostic::new(self.spec.severity, self.id(), self.spec.message.clone())
+            .with_file(&file.path);
+
+        Some(match &self.spec.replacement {
+            Some(replacement) => {
+                // Keep a blank line between the existing content and the new section
+                let mut text = String::new();
+                if !file.content.is_empty() {
+                    let newlines = file.content.len() - file.content.trim_end_matches('\n').len();
+                    text.push_str(&"\n".repeat(2usize.saturating_sub(newlines)));
+                }
+                text.push_str(replacement);
+                if !replacement.ends_with('\n') {
+                    text.push('\n');
+                }
+                diagnostic.with_fix(
+                    Fix::new(format!(
+                        "Append `{}`",
+                        replacement.lines().next().unwrap_or_default()
+                    ))
+                    .with_edit(TextEdit::insert(
+                        &file.path,
+                        file.content.len(),
+                        text,
+                    )),
+                )
+            }
+            None => diagnostic,
+        })
+    }
+}
+
+impl Rule for CustomRule {
+    fn id(&self) -> &str {
+        &self.spec.id
+    }
+
+    fn default_level(&self) -> DiagnosticLevel {
+        self.spec.severity
+    }
+
+    fn description(&self) -> &str {
+        self.spec
+            .description
+            .as_deref()
+            .unwrap_or(&self.spec.message)
+    }
+
+    fn enabled_by_default(&self) -> bool {
+        !self.spec.opt_in
+    }
+
+    fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        ctx.files
+            .iter()
+            .filter(|file| self.in_scope(file, ctx.project_root))
+            .flat_map(|file| match &self.condition {
+                Condition::Pattern(regex) => self.check_pattern(file, regex, ctx.parser),
+                Condition::RequireHeading(regex) => self
+                    .check_heading(file, regex, ctx.parser)
+                    .into_iter()
+                    .collect(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::rules::RuleRegistry;
+    use crate::analyzer::Diagnostics;
+    use crate::core::{Config, ImportGraph};
+
+    fn spec(id: &str, pattern: &str) -> CustomRuleSpec {
+        toml::from_str(&format!(
+            "id = {:?}\nmessage = \"Found {{match}}\"\npattern = {:?}\n",
+            id, pattern
+        ))
+        .unwrap()
+    }
+
+    fn messages(rule: &dyn Rule, content: &str) -> Vec<(usize, String)> {
+        let files = [MemoryFile::from_content(
+            "/repo/CLAUDE.md",
+            MemoryType::ProjectMemory,
+            content,
+        )];
+        let (graph, config) = (ImportGraph::new(), Config::default());
+        rule.check(&RuleContext {
+            files: &files,
+            graph: &graph,
+            config: &config,
+            project_root: Path::new("/repo"),
+            parser: &Parser::new(),
+        })
+        .into_iter()
+        .map(|d| (d.line.unwrap(), d.message))
+        .collect()
+    }
+
+    #[test]
+    fn loads_toml_and_yaml_rule_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let toml_file = dir.path().join("rules.toml");
+        fs::write(
+            &toml_file,
+            "[[rules]]\nid = \"team/no-todo\"\nmessage = \"Resolve {match}\"\npattern = \"TODO\"\n",
+        )
+        .unwrap();
+        let yaml_file = dir.path().join("rules.yml");
+        fs::write(
+            &yaml_file,
+            "rules:\n  - id: team/testing\n    severity: error\n    message: Add a Testing section\n    require_heading: ^Testing$\n    opt_in: true\n",
+        )
+        .unwrap();
+
+        let rules = load_file(&toml_file).unwrap();
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].id(), "team/no-todo");
+        assert_eq!(rules[0].default_level(), DiagnosticLevel::Warning);
+        assert_eq!(
+            messages(&rules[0], "# Notes\nTODO: write\n"),
+            vec![(2, "Resolve TODO".to_string())]
+        );
+
+        let rules = load_file(&yaml_file).unwrap();
+        assert_eq!(rules[0].id(), "team/testing");
+        assert_eq!(rules[0].default_level(), DiagnosticLevel::Error);
+        assert!(!rules[0].enabled_by_default());
+
+        let unsupported = dir.path().join("rules.json");
+        fs::write(&unsupported, "{}").unwrap();
+        let err = load_file(&unsupported).err().unwrap();
+        assert!(
+            err.to_string().contains("Unsupported rule file"),
+            "{:#}",
+            err
+        );
+    }
+
+    #[test]
+    fn registers_rules_from_the_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_file = dir.path().join(".ccat.toml");
+        fs::write(
+            &config_file,
+            "[[diagnostics.rules]]\nid = \"no-npm\"\nmessage = \"Use pnpm, not {match}\"\n\
+             pattern = \"\\\\bnpm\\\\b\"\nreplacement = \"pnpm\"\n",
+        )
+        .unwrap();
+        let config = Config::from_file(&config_file).unwrap();
+
+        let registry = RuleRegistry::with_custom_rules(&config, dir.path(), &[]).unwrap();
+        let rule = registry.get("no-npm").unwrap();
+        assert!(registry.get("large-file").is_some());
+        assert_eq!(
+            messages(rule, "Run `npm test`.\n"),
+            vec![(1, "Use pnpm, not npm".to_string())]
+        );
+    }
+
+    #[test]
+    fn rejects_bad_regexes() {
+        let err = CustomRule::new(spec("broken", "(unclosed")).err().unwrap();
+        assert!(
+            format!("{:#}", err).contains("Invalid regex in custom rule broken"),
+            "{:#}",
+            err
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("rules.yaml");
+        fs::write(
+            &file,
+            "rules:\n  - id: heading\n    message: m\n    require_heading: \"[\"\n",
+        )
+        .unwrap();
+        let err = load_file(&file).err().unwrap();
+        assert!(
+            err.to_string().starts_with("Invalid rule file"),
+            "{:#}",
+            err
+        );
+        assert!(format!("{:#}", err).contains("Invalid regex in custom rule heading"));
+    }
+
+    #[test]
+    fn rejects_duplicate_rule_ids() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("rules.toml");
+        fs::write(
+            &file,
+            "[[rules]]\nid = \"no-todo\"\nmessage = \"m\"\npattern = \"TODO\"\n",
+        )
+        .unwrap();
+
+        let mut config = Config::default();
+        config.diagnostics.rules.push(spec("no-todo", "FIXME"));
+        let err = RuleRegistry::with_custom_rules(&config, dir.path(), &[file])
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.to_string(),
+            "Custom rule no-todo conflicts with an existing rule"
+        );
+
+        let mut config = Config::default();
+        config.diagnostics.rules.push(spec("large-file", "x"));
+        let err = RuleRegistry::with_custom_rules(&config, dir.path(), &[])
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.to_string(),
+            "Custom rule large-file conflicts with an existing rule"
+        );
+    }
+
+    #[test]
+    fn opt_in_rules_only_run_when_enabled() {
+        let mut spec = spec("no-todo", "TODO");
+        spec.opt_in = true;
+        let files = [MemoryFile::from_content(
+            "/repo/CLAUDE.md",
+            MemoryType::ProjectMemory,
+            "TODO\n",
+        )];
+        let codes = |diagnostics: Diagnostics| -> Vec<String> {
+            let mut registry = RuleRegistry::new();
+            registry.register(Box::new(CustomRule::new(spec.clone()).unwrap()));
+            diagnostics
+                .with_registry(registry)
+                .check(&files)
+                .unwrap()
+                .diagnostics()
+                .map(|d| d.code.clone())
+                .collect()
+        };
+
+        assert!(codes(Diagnostics::new()).is_empty());
+        assert_eq!(codes(Diagnostics::new().with_all_rules(true)), ["no-todo"]);
+        assert_eq!(
+            codes(Diagnostics::new().with_enabled_rules(["no-todo"])),
+            ["no-todo"]
+        );
+    }
+}
@@ -0,0 +1,187 @@
+use super::imports::import_span;
+use super::{Rule, RuleContext};
+use crate::analyzer::fix::{line_bounds, Fix, TextEdit};
+use crate::analyzer::{Diagnostic, DiagnosticLevel};
+use crate::core::resolver::expand_home;
+use crate::core::{MemoryFile, MemoryType};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const LARGE_FILE_BYTES: u64 = 1_000_000;
+
+pub struct LargeFile;
+
+impl Rule for LargeFile {
+    fn id(&self) -> &str {
+        "large-file"
+    }
+
+    fn default_level(&self) -> DiagnosticLevel {
+        DiagnosticLevel::Warning
+    }
+
+    fn description(&self) -> &str {
+        "Memory file is larger than 1MB"
+    }
+
+    fn explanation(&self) -> &str {
+        "Memory files are loaded into every conversation. A file over 1MB is far beyond \
+         any useful context size; split it up or move rarely needed material into \
+         section imports."
+    }
+
+    fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        ctx.files
+            .iter()
+            .filter(|file| file.metadata.size > LARGE_FILE_BYTES)
+            .map(|file| {
+                Diagnostic::new(
+                    self.default_level(),
+                    self.id(),
+                    format!("File is larger than 1MB: {}", file.path.display()),
+                )
+                .with_file(&file.path)
+            })
+            .collect()
+    }
+}
+
+pub struct DeprecatedLocalMemory;
+
+impl Rule for DeprecatedLocalMemory {
+    fn id(&self) -> &str {
+        "deprecated-local-memory"
+    }
+
+    fn default_level(&self) -> DiagnosticLevel {
+        DiagnosticLevel::Warning
+    }
+
+    fn description(&self) -> &str {
+        "CLAUDE.local.md is deprecated in favour of home directory imports"
+    }
+
+    fn explanation(&self) -> &str {
+        "Personal project instructions belong in a file under `~/.claude`, imported from \
+         `~/.claude/CLAUDE.md` or the project CLAUDE.md, e.g. \
+         `@~/.claude/my-project-instructions.md`. There is no automatic fix, since moving \
+         the file writes outside the project; `ccat migrate` moves it, or keeps it and makes \
+         sure git ignores it."
+    }
+
+    fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        ctx.files
+            .iter()
+            .filter(|file| file.file_type == MemoryType::LocalMemory)
+            .map(|file| {
+                Diagnostic::new(
+                    self.default_level(),
+                    self.id(),
+                    "CLAUDE.local.md is deprecated; move it with `ccat migrate`",
+                )
+                .with_file(&file.path)
+            })
+            .collect()
+    }
+}
+
+/// Home directory import the CLAUDE.local.md of `project_root` moves to:
+/// `~/.claude/<project>-instructions.md`.
+pub(crate) fn home_import(project_root: &Path) -> String {
+    let project = project_root
+        .canonicalize()
+        .ok()
+        .and_then(|root| Some(root.file_name()?.to_string_lossy().to_string()))
+        .unwrap_or_else(|| "project".to_string());
+    format!("~/.claude/{}-instructions.md", project)
+}
+
+/// File an import written next to `local` refers to.
+pub(crate) fn local_import_target(local: &MemoryFile, import: &str) -> PathBuf {
+    local
+        .path
+        .parent()
+        .unwrap_or(Path::new("."))
+        .join(expand_home(import))
+}
+
+/// Moves a CLAUDE.local.md into the file `import` names and imports it from
+/// the CLAUDE.md next to it. Imports of CLAUDE.local.md in other memory files
+/// are pointed at the new file. With `edit_imports` unset, only the file is
+/// moved and no memory file is touched. Returns `None` if the target already
+/// exists.
+pub(crate) fn move_local_memory(
+    local: &MemoryFile,
+    files: &[MemoryFile],
+    import: &str,
+    edit_imports: bool,
+) -> Option<Fix> {
+    let target = local_import_target(local, import);
+    if target.exists() {
+        return None;
+    }
+
+    let mut fix = Fix::new(format!("Move CLAUDE.local.md to {}", import))
+        .with_edit(TextEdit::insert(&target, 0, local.content.clone()))
+        .with_delete(&local.path);
+    if !edit_imports {
+        return Some(fix);
+    }
+
+    let canonical = local.path.canonicalize().ok()?;
+    let memory = local.path.with_file_name("CLAUDE.md");
+    let mut imported = false;
+    for file in files.iter().filter(|file| file.path != local.path) {
+        for reference in file
+            .imports
+            .iter()
+            .filter(|i| i.resolved_path.as_ref() == Some(&canonical))
+        {
+            let Some((line_start, line_end)) = line_bounds(&file.content, reference.line_number)
+            else {
+                continue;
+            };
+            let line = &file.content[line_start..line_end];
+            let Some((start, end)) = import_span(line, reference) else {
+                continue;
+            };
+
+            let path = reference.path.replacen(reference.file_path(), import, 1);
+            let replacement = if line[start..].starts_with('@') {
+                format!("@{}", path)
+            } else {
+                format!("{{{{import {}}}}}", path)
+            };
+            fix = fix.with_edit(TextEdit::replace(
+                &file.path,
+                line_start + start,
+                line_start + end,
+                replacement,
+            ));
+            imported |= file.path == memory;
+        }
+    }
+    if imported {
+        return Some(fix);
+    }
+
+    let content = files
+        .iter()
+        .find(|file| file.path == memory)
+        .map(|file| file.content.clone())
+        .or_else(|| fs::read_to_string(&memory).ok())
+        .unwrap_or_default();
+    let separator = if content.is_empty() || content.ends_with("\n\n") {
+        ""
+    } else if content.ends_with('\n') {
+        "\n"
+    } else {
+        "\n\n"
+    };
+
+    Some(fix.with_edit(TextEdit::insert(
+        &memory,
+        content.len(),
+        format!("{}@{}\n", separator, import),
+    )))
+}
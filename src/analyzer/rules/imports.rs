@@ -0,0 +1,554 @@
+use super::{Rule, RuleContext};
+use crate::analyzer::fix::{line_bounds, Fix, TextEdit};
+use crate::analyzer::{Diagnostic, DiagnosticLevel};
+use crate::core::{Import, ImportSelector, MemoryFile, MAX_IMPORT_DEPTH};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+static IMPORT_DIRECTIVE_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\{\{import\s+([^\}]+)\}\}").unwrap());
+
+pub struct MissingImport;
+
+impl Rule for MissingImport {
+    fn id(&self) -> &str {
+        "missing-import"
+    }
+
+    fn default_level(&self) -> DiagnosticLevel {
+        DiagnosticLevel::Error
+    }
+
+    fn description(&self) -> &str {
+        "Import does not resolve to an existing file"
+    }
+
+    fn explanation(&self) -> &str {
+        "Imports are resolved relative to the importing file, then the project root, then \
+         as absolute paths, with and without a `.md` extension. An import that matches none \
+         of these is silently dropped from the context."
+    }
+
+    fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for file in ctx.files {
+            for import in file.imports.iter().filter(|i| !i.is_glob()) {
+                if import.resolved_path.is_none() && import.policy_violations.is_empty() {
+                    let diagnostic = Diagnostic::new(
+                        self.default_level(),
+                        self.id(),
+                        format!("Unresolved import: {}", import.path),
+                    )
+                    .with_file(&file.path)
+                    .with_line(import.line_number);
+                    diagnostics.push(match remove_import(file, import) {
+                        Some(fix) => diagnostic.with_fix(fix),
+                        None => diagnostic,
+                    });
+                }
+            }
+        }
+
+        diagnostics
+    }
+}
+
+/// Removes the import from its line, or the whole line if nothing else is
+/// left on it.
+fn remove_import(file: &MemoryFile, import: &Import) -> Option<Fix> {
+    let (line_start, line_end) = line_bounds(&file.content, import.line_number)?;
+    let line = &file.content[line_start..line_end];
+    let (mut start, end) = import_span(line, import)?;
+
+    let rest = format!("{}{}", &line[..start], &line[end..]);
+    let edit = if rest.trim().is_empty() {
+        TextEdit::delete(&file.path, line_start, line_end)
+    } else {
+        if line[..start].ends_with(' ') {
+            start -= 1;
+        }
+        TextEdit::delete(&file.path, line_start + start, line_start + end)
+    };
+
+    Some(Fix::new(format!("Remove unresolved import {}", import.path)).with_edit(edit))
+}
+
+/// Byte range of the `{{import}}` directive or `@path` reference for
+/// `import` within `line`.
+pub(super) fn import_span(line: &str, import: &Import) -> Option<(usize, usize)> {
+    let directive = IMPORT_DIRECTIVE_REGEX
+        .captures_iter(line)
+        .find(|captures| captures[1].trim() == import.path)
+        .and_then(|captures| captures.get(0))
+        .map(|m| (m.start(), m.end()));
+    let at_import = || {
+        let token = format!("@{}", import.path);
+        line.match_indices(&token)
+            .map(|(start, _)| (start, start + token.len()))
+            .find(|&(start, end)| {
+                let before = line[..start].chars().next_back();
+                let after = line[end..].chars().next();
+                before.is_none_or(char::is_whitespace)
+                    && after.is_none_or(|c| c.is_whitespace() || ".,;:)".contains(c))
+            })
+    };
+    directive.or_else(at_import)
+}
+
+pub struct InvalidImportSelector;
+
+impl Rule for InvalidImportSelector {
+    fn id(&self) -> &str {
+        "invalid-import-selector"
+    }
+
+    fn default_level(&self) -> DiagnosticLevel {
+        DiagnosticLevel::Error
+    }
+
+    fn description(&self) -> &str {
+        "Section or line-range import does not match the target file"
+    }
+
+    fn explanation(&self) -> &str {
+        "`@file.md#section` must name a heading in the target (by title or anchor slug) and \
+         `@file:10-40` must start within the file. Otherwise nothing is imported."
+    }
+
+    fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for file in ctx.files {
+            for import in &file.imports {
+                let (Some(selector), Some(resolved)) = (&import.selector, &import.resolved_path)
+                else {
+                    continue;
+                };
+                if import.selected_lines.is_some() {
+                    continue;
+                }
+
+                let message = match selector {
+                    ImportSelector::Section(name) => {
+                        format!("Section `{}` not found in {}", name, resolved.display())
+                    }
+                    ImportSelector::Lines { start, end } => format!(
+                        "Line range {}-{} is outside {}",
+                        start,
+                        end,
+                        resolved.display()
+                    ),
+                };
+                diagnostics.push(
+                    Diagnostic::new(self.default_level(), self.id(), message)
+                        .with_file(&file.path)
+                        .with_line(import.line_number),
+                );
+            }
+        }
+
+        diagnostics
+    }
+}
+
+pub struct ImportPolicyViolation;
+
+impl Rule for ImportPolicyViolation {
+    fn id(&self) -> &str {
+        "import-policy"
+    }
+
+    fn default_level(&self) -> DiagnosticLevel {
+        DiagnosticLevel::Error
+    }
+
+    fn description(&self) -> &str {
+        "Import target is refused by the import policy"
+    }
+
+    fn explanation(&self) -> &str {
+        "The `[imports.policy]` configuration limits imports to allowed roots and \
+         extensions and refuses credential-like paths. Denied targets are never expanded. \
+         Imports in imported files are checked as well."
+    }
+
+    fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        let scanned = ctx.files.iter().flat_map(|file| {
+            file.imports
+                .iter()
+                .map(move |import| (file.path.as_path(), import))
+        });
+        let imported = ctx
+            .graph
+            .denied_imports()
+            .iter()
+            .map(|(path, import)| (path.as_path(), import));
+
+        let mut diagnostics = Vec::new();
+        for (path, import) in scanned.chain(imported) {
+            for violation in &import.policy_violations {
+                diagnostics.push(
+                    Diagnostic::new(
+                        self.default_level(),
+                        self.id(),
+                        format!("Import {} denied: {}", import.path, violation),
+                    )
+                    .with_file(path)
+                    .with_line(import.line_number),
+                );
+            }
+        }
+
+        diagnostics
+    }
+}
+
+pub struct EmptyGlobImport;
+
+impl Rule for EmptyGlobImport {
+    fn id(&self) -> &str {
+        "empty-glob-import"
+    }
+
+    fn default_level(&self) -> DiagnosticLevel {
+        DiagnosticLevel::Warning
+    }
+
+    fn description(&self) -> &str {
+        "Glob or directory import matches no files"
+    }
+
+    fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for file in ctx.files {
+            for import in file.imports.iter().filter(|i| i.is_glob()) {
+                if !import.is_resolved()
+                    && import.policy_violations.is_empty()
+                    && import.pattern_error.is_none()
+                {
+                    diagnostics.push(
+                        Diagnostic::new(
+                            self.default_level(),
+                            self.id(),
+                            format!("Glob import matches no files: {}", import.path),
+                        )
+                        .with_file(&file.path)
+                        .with_line(import.line_number),
+                    );
+                }
+            }
+        }
+
+        diagnostics
+    }
+}
+
+pub struct InvalidGlobImport;
+
+impl Rule for InvalidGlobImport {
+    fn id(&self) -> &str {
+        "invalid-glob-import"
+    }
+
+    fn default_level(&self) -> DiagnosticLevel {
+        DiagnosticLevel::Error
+    }
+
+    fn description(&self) -> &str {
+        "Glob import pattern is malformed"
+    }
+
+    fn explanation(&self) -> &str {
+        "A glob import such as `@docs/[abc.md` whose pattern cannot be parsed, e.g. because of \
+         an unclosed `[`, matches nothing. Fix the pattern, or escape the character as `[[]`."
+    }
+
+    fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for file in ctx.files {
+            for import in &file.imports {
+                if let Some(error) = &import.pattern_error {
+                    diagnostics.push(
+                        Diagnostic::new(
+                            self.default_level(),
+                            self.id(),
+                            format!("Invalid glob import {}: {}", import.path, error),
+                        )
+                        .with_file(&file.path)
+                        .with_line(import.line_number),
+                    );
+                }
+            }
+        }
+
+        diagnostics
+    }
+}
+
+pub struct DuplicateGlobImport;
+
+impl Rule for DuplicateGlobImport {
+    fn id(&self) -> &str {
+        "duplicate-glob-import"
+    }
+
+    fn default_level(&self) -> DiagnosticLevel {
+        DiagnosticLevel::Warning
+    }
+
+    fn description(&self) -> &str {
+        "File is imported both individually and through a glob"
+    }
+
+    fn explanation(&self) -> &str {
+        "A file matched by a glob import in the same memory file is also imported on its \
+         own, so its content is loaded twice. Drop the individual import or narrow the glob."
+    }
+
+    fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for file in ctx.files {
+            for import in file.imports.iter().filter(|i| i.is_glob()) {
+                for matched in &import.resolved_paths {
+                    let individual = file
+                        .imports
+                        .iter()
+                        .find(|i| i.resolved_path.as_ref() == Some(matched));
+                    if let Some(individual) = individual {
+                        diagnostics.push(
+                            Diagnostic::new(
+                                self.default_level(),
+                                self.id(),
+                                format!(
+                                    "{} is imported individually (line {}) and via glob {}",
+                                    individual.path, individual.line_number, import.path
+                                ),
+                            )
+                            .with_file(&file.path)
+                            .with_line(import.line_number),
+                        );
+                    }
+                }
+            }
+        }
+
+        diagnostics
+    }
+}
+
+pub struct MissingAliasTarget;
+
+impl Rule for MissingAliasTarget {
+    fn id(&self) -> &str {
+        "missing-alias-target"
+    }
+
+    fn default_level(&self) -> DiagnosticLevel {
+        DiagnosticLevel::Warning
+    }
+
+    fn description(&self) -> &str {
+        "Import alias points to a directory that does not exist"
+    }
+
+    fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        ctx.config
+            .imports
+            .alias_dirs(ctx.project_root)
+            .into_iter()
+            .filter(|(_, dir)| !dir.is_dir())
+            .map(|(alias, dir)| {
+                let diagnostic = Diagnostic::new(
+                    self.default_level(),
+                    self.id(),
+                    format!(
+                        "Import alias `{}` points to missing directory: {}",
+                        alias,
+                        dir.display()
+                    ),
+                );
+                match &ctx.config.path {
+                    Some(config_file) => diagnostic.with_file(config_file),
+                    None => diagnostic,
+                }
+            })
+            .collect()
+    }
+}
+
+pub struct CircularImport;
+
+impl Rule for CircularImport {
+    fn id(&self) -> &str {
+        "circular-import"
+    }
+
+    fn default_level(&self) -> DiagnosticLevel {
+        DiagnosticLevel::Error
+    }
+
+    fn description(&self) -> &str {
+        "Files import each other in a cycle"
+    }
+
+    fn explanation(&self) -> &str {
+        "Each strongly connected component of the import graph is reported once, starting \
+         at its first file and following the shortest import chain back to it."
+    }
+
+    fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        ctx.graph
+            .cycles()
+            .into_iter()
+            .map(|cycle| {
+                let origin = &cycle.cycle[0];
+                let next = cycle.cycle.get(1).unwrap_or(origin);
+                let diagnostic =
+                    Diagnostic::new(self.default_level(), self.id(), cycle.to_string())
+                        .with_file(ctx.scanned_path(origin));
+                match ctx.graph.import_line(origin, next) {
+                    Some(line) => diagnostic.with_line(line),
+                    None => diagnostic,
+                }
+            })
+            .collect()
+    }
+}
+
+pub struct DeepImportChain;
+
+impl Rule for DeepImportChain {
+    fn id(&self) -> &str {
+        "deep-import-chain"
+    }
+
+    fn default_level(&self) -> DiagnosticLevel {
+        DiagnosticLevel::Warning
+    }
+
+    fn description(&self) -> &str {
+        "Import chain is deeper than Claude Code follows"
+    }
+
+    fn explanation(&self) -> &str {
+        "Claude Code follows imports of imported files at most five hops deep; files further \
+         away are silently left out of the context. A file counts as loaded if any import \
+         chain from the memory file reaches it within five hops, so each memory file is \
+         reported with the nearest file it does not load and the shortest chain to it. \
+         Import the deep files directly from a file closer to the memory file instead."
+    }
+
+    fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        ctx.files
+            .iter()
+            .filter_map(|file| {
+                let path = file.path.canonicalize().unwrap_or_else(|_| file.path.clone());
+                let unloaded: Vec<_> = ctx
+                    .graph
+                    .shortest_chains(&path)
+                    .into_values()
+                    .filter(|chain| chain.len() > MAX_IMPORT_DEPTH + 1)
+                    .collect();
+                let chain = unloaded.iter().min_by_key(|chain| chain.len())?;
+
+                let more = match unloaded.len() - 1 {
+                    0 => String::new(),
+                    1 => " (and 1 more file)".to_string(),
+                    n => format!(" (and {} more files)", n),
+                };
+                let message = format!(
+                    "{} is {} imports away, but Claude Code follows at most {}; it is not loaded{}: {}",
+                    chain[chain.len() - 1].display(),
+                    chain.len() - 1,
+                    MAX_IMPORT_DEPTH,
+                    more,
+                    chain
+                        .iter()
+                        .map(|p| p.display().to_string())
+                        .collect::<Vec<_>>()
+                        .join(" -> ")
+                );
+                let diagnostic =
+                    Diagnostic::new(self.default_level(), self.id(), message).with_file(&file.path);
+                Some(match ctx.graph.import_line(&chain[0], &chain[1]) {
+                    Some(line) => diagnostic.with_line(line),
+                    None => diagnostic,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Config, ImportResolver, Parser, Scanner};
+    use std::fs;
+
+    #[test]
+    fn reports_policy_violations_of_imported_files() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("docs")).unwrap();
+        fs::write(dir.path().join("CLAUDE.md"), "@docs/setup.md\n").unwrap();
+        fs::write(dir.path().join("docs/setup.md"), "# Setup\n\n@../.env\n").unwrap();
+        fs::write(dir.path().join(".env"), "TOKEN=1\n").unwrap();
+
+        let config = Config::default();
+        let mut files = Scanner::new().scan(dir.path()).unwrap();
+        let mut resolver = ImportResolver::from_config(dir.path(), &config);
+        resolver.resolve_all(&mut files).unwrap();
+        let graph = resolver.build_graph(&files).unwrap();
+
+        let diagnostics = ImportPolicyViolation.check(&RuleContext {
+            files: &files,
+            graph: &graph,
+            config: &config,
+            project_root: dir.path(),
+            parser: &Parser::new(),
+        });
+        assert_eq!(diagnostics.len(), 1);
+        let file = diagnostics[0].file.as_deref().unwrap();
+        assert!(file.ends_with("setup.md"), "{}", file);
+        assert_eq!(diagnostics[0].line, Some(3));
+    }
+
+    #[test]
+    fn reports_malformed_glob_patterns_without_aborting() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("docs")).unwrap();
+        fs::write(dir.path().join("docs/[abc.md"), "").unwrap();
+        fs::write(
+            dir.path().join("CLAUDE.md"),
+            "# Project\n@docs/[abc.md\n@docs/[[]abc.md\n",
+        )
+        .unwrap();
+
+        let config = Config::default();
+        let mut files = Scanner::new().scan(dir.path()).unwrap();
+        let mut resolver = ImportResolver::from_config(dir.path(), &config);
+        resolver.resolve_all(&mut files).unwrap();
+        let graph = resolver.build_graph(&files).unwrap();
+        let ctx = RuleContext {
+            files: &files,
+            graph: &graph,
+            config: &config,
+            project_root: dir.path(),
+            parser: &Parser::new(),
+        };
+
+        let invalid = InvalidGlobImport.check(&ctx);
+        assert_eq!(invalid.len(), 1);
+        assert_eq!(invalid[0].line, Some(2));
+        assert!(invalid[0].file.as_deref().unwrap().ends_with("CLAUDE.md"));
+        assert!(invalid[0]
+            .message
+            .starts_with("Invalid glob import docs/[abc.md: "));
+        // Neither reported as empty, and the escaped pattern resolves
+        assert!(EmptyGlobImport.check(&ctx).is_empty());
+        assert_eq!(files[0].imports[1].resolved_paths.len(), 1);
+    }
+}
@@ -0,0 +1,884 @@
+use super::{Rule, RuleContext};
+use crate::analyzer::fix::{Fix, TextEdit};
+use crate::analyzer::tokens::estimate_tokens;
+use crate::analyzer::{Diagnostic, DiagnosticLevel};
+use crate::core::parser::slugify;
+use crate::core::MemoryFile;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::HashMap;
+
+const MAX_SECTION_LINES: usize = 200;
+const MAX_SECTION_TOKENS: usize = 3_000;
+
+static TABLE_DELIMITER_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\s*\|?\s*:?-+:?\s*(\|\s*:?-+:?\s*)*\|?\s*$").unwrap());
+
+/// Tracks fenced code blocks. A block is closed by a fence of the same
+/// character that is at least as long as the opening one.
+#[derive(Default)]
+struct Fences {
+    /// Line, character and length of the open fence.
+    open: Option<(usize, char, usize)>,
+}
+
+impl Fences {
+    /// Feeds the next line and returns whether it is a fence line.
+    fn update(&mut self, line_number: usize, text: &str) -> bool {
+        let trimmed = text.trim_start();
+        let Some(marker) = trimmed.chars().next().filter(|c| *c == '`' || *c == '~') else {
+            return false;
+        };
+        let rest = trimmed.trim_start_matches(marker);
+        let len = trimmed.len() - rest.len();
+        if len < 3 {
+            return false;
+        }
+
+        match self.open {
+            None => {
+                self.open = Some((line_number, marker, len));
+                true
+            }
+            Some((_, open_marker, open_len))
+                if marker == open_marker && len >= open_len && rest.trim().is_empty() =>
+            {
+                self.open = None;
+                true
+            }
+            Some(_) => false,
+        }
+    }
+}
+
+/// Lines of a file with their byte offset, line number and whether they are
+/// inside a fenced code block. Fence lines themselves count as code.
+pub(super) fn lines(file: &MemoryFile) -> Vec<(usize, usize, &str, bool)> {
+    let mut lines = Vec::new();
+    let mut offset = 0;
+    let mut fences = Fences::default();
+
+    for (idx, line) in file.content.split_inclusive('\n').enumerate() {
+        let text = line.trim_end_matches(['\n', '\r']);
+        let is_fence = fences.update(idx + 1, text);
+        lines.push((offset, idx + 1, text, fences.open.is_some() || is_fence));
+        offset += line.len();
+    }
+
+    lines
+}
+
+struct Heading<'a> {
+    offset: usize,
+    line: usize,
+    level: usize,
+    title: &'a str,
+}
+
+/// ATX headings outside code blocks.
+fn headings(file: &MemoryFile) -> Vec<Heading<'_>> {
+    lines(file)
+        .into_iter()
+        .filter(|(_, _, _, in_code)| !in_code)
+        .filter_map(|(offset, line, text, _)| {
+            let level = text.len() - text.trim_start_matches('#').len();
+            let rest = &text[level..];
+            if !(1..=6).contains(&level) || !(rest.is_empty() || rest.starts_with([' ', '\t'])) {
+                return None;
+            }
+            Some(Heading {
+                offset,
+                line,
+                level,
+                title: rest.trim().trim_end_matches('#').trim_end(),
+            })
+        })
+        .collect()
+}
+
+/// Number of cells in a table row. Leading and trailing pipes are optional
+/// and escaped pipes do not separate cells.
+fn table_cells(row: &str) -> usize {
+    let row = row.trim();
+    let row = row.strip_prefix('|').unwrap_or(row);
+    let row = match row.strip_suffix('|') {
+        Some(stripped) if !stripped.ends_with('\\') => stripped,
+        _ => row,
+    };
+
+    let mut cells = 1;
+    let mut escaped = false;
+    for c in row.chars() {
+        match c {
+            '\\' if !escaped => escaped = true,
+            '|' if !escaped => cells += 1,
+            _ => escaped = false,
+        }
+    }
+    cells
+}
+
+pub struct TrailingWhitespace;
+
+impl Rule for TrailingWhitespace {
+    fn id(&self) -> &str {
+        "trailing-whitespace"
+    }
+
+    fn default_level(&self) -> DiagnosticLevel {
+        DiagnosticLevel::Info
+    }
+
+    fn description(&self) -> &str {
+        "Line ends with whitespace"
+    }
+
+    fn explanation(&self) -> &str {
+        "Trailing spaces and tabs cost tokens without changing the meaning. Exactly two \
+         trailing spaces after text are kept, since Markdown reads them as a line break, \
+         and lines in fenced code blocks are left alone."
+    }
+
+    fn enabled_by_default(&self) -> bool {
+        false
+    }
+
+    fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for file in ctx.files {
+            for (offset, line_number, text, in_code) in lines(file) {
+                // Whitespace in code blocks may be significant
+                if in_code {
+                    continue;
+                }
+                let trimmed = text.trim_end_matches([' ', '\t']);
+                let trailing = &text[trimmed.len()..];
+                let is_line_break = !trimmed.is_empty() && trailing == "  ";
+                if trailing.is_empty() || is_line_break {
+                    continue;
+                }
+
+                let start = offset + trimmed.len();
+                diagnostics.push(
+                    Diagnostic::new(self.default_level(), self.id(), "Trailing whitespace")
+                        .with_file(&file.path)
+                        .with_line(line_number)
+                        .with_column(trimmed.chars().count() + 1)
+                        .with_fix(Fix::new("Remove trailing whitespace").with_edit(
+                            TextEdit::delete(&file.path, start, start + trailing.len()),
+                        )),
+                );
+            }
+        }
+
+        diagnostics
+    }
+}
+
+pub struct FinalNewline;
+
+impl Rule for FinalNewline {
+    fn id(&self) -> &str {
+        "final-newline"
+    }
+
+    fn default_level(&self) -> DiagnosticLevel {
+        DiagnosticLevel::Info
+    }
+
+    fn description(&self) -> &str {
+        "File does not end with a newline"
+    }
+
+    fn enabled_by_default(&self) -> bool {
+        false
+    }
+
+    fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        ctx.files
+            .iter()
+            .filter(|file| !file.content.is_empty() && !file.content.ends_with('\n'))
+            .map(|file| {
+                Diagnostic::new(
+                    self.default_level(),
+                    self.id(),
+                    "Missing newline at end of file",
+                )
+                .with_file(&file.path)
+                .with_line(file.content.lines().count())
+                .with_fix(
+                    Fix::new("Add final newline").with_edit(TextEdit::insert(
+                        &file.path,
+                        file.content.len(),
+                        "\n",
+                    )),
+                )
+            })
+            .collect()
+    }
+}
+
+pub struct HeadingSpace;
+
+impl Rule for HeadingSpace {
+    fn id(&self) -> &str {
+        "heading-space"
+    }
+
+    fn default_level(&self) -> DiagnosticLevel {
+        DiagnosticLevel::Warning
+    }
+
+    fn description(&self) -> &str {
+        "Heading marker is not followed by a space"
+    }
+
+    fn explanation(&self) -> &str {
+        "`#Title` is plain text, not a heading, so it starts no section and cannot be the \
+         target of a `#section` import. Add a space after the `#` characters. Only lines that \
+         read like a title are reported: a single `#` must be followed by a capital letter, \
+         and lines ending like a sentence are skipped, so `#123` issue references and \
+         `#hashtags` are left alone."
+    }
+
+    fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for file in ctx.files {
+            for (offset, line_number, text, in_code) in lines(file) {
+                let hashes = text.len() - text.trim_start_matches('#').len();
+                if in_code || !(1..=6).contains(&hashes) || !is_title(hashes, &text[hashes..]) {
+                    continue;
+                }
+
+                diagnostics.push(
+                    Diagnostic::new(
+                        self.default_level(),
+                        self.id(),
+                        format!("Missing space after heading marker: {}", text),
+                    )
+                    .with_file(&file.path)
+                    .with_line(line_number)
+                    .with_column(hashes + 1)
+                    .with_fix(
+                        Fix::new("Insert space after heading marker").with_edit(TextEdit::insert(
+                            &file.path,
+                            offset + hashes,
+                            " ",
+                        )),
+                    ),
+                );
+            }
+        }
+
+        diagnostics
+    }
+}
+
+/// Whether `rest`, the text right after `hashes` heading markers, reads like
+/// a heading title rather than an issue reference, hashtag or sentence.
+fn is_title(hashes: usize, rest: &str) -> bool {
+    let Some(first) = rest.chars().next() else {
+        return false;
+    };
+    let starts_like_title = if hashes == 1 {
+        first.is_uppercase()
+    } else {
+        first.is_alphabetic()
+    };
+    let rest = rest.trim_end();
+    starts_like_title && rest.chars().count() <= 80 && !rest.ends_with(['.', ',', ';'])
+}
+
+pub struct HeadingIncrement;
+
+impl Rule for HeadingIncrement {
+    fn id(&self) -> &str {
+        "heading-increment"
+    }
+
+    fn default_level(&self) -> DiagnosticLevel {
+        DiagnosticLevel::Warning
+    }
+
+    fn description(&self) -> &str {
+        "Heading level skips a level"
+    }
+
+    fn explanation(&self) -> &str {
+        "A heading should be at most one level deeper than the heading before it, e.g. `##` \
+         after `#`. Skipped levels make the outline, and the sections picked out by `#section` \
+         imports, harder to follow. The fix raises the heading to the next level."
+    }
+
+    fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for file in ctx.files {
+            let headings = headings(file);
+            for pair in headings.windows(2) {
+                let (previous, heading) = (&pair[0], &pair[1]);
+                if heading.level <= previous.level + 1 {
+                    continue;
+                }
+
+                let expected = previous.level + 1;
+                diagnostics.push(
+                    Diagnostic::new(
+                        self.default_level(),
+                        self.id(),
+                        format!(
+                            "Heading level {} follows level {} on line {}; expected level {}",
+                            heading.level, previous.level, previous.line, expected
+                        ),
+                    )
+                    .with_file(&file.path)
+                    .with_line(heading.line)
+                    .with_column(1)
+                    .with_fix(
+                        Fix::new(format!("Change heading to level {}", expected)).with_edit(
+                            TextEdit::replace(
+                                &file.path,
+                                heading.offset,
+                                heading.offset + heading.level,
+                                "#".repeat(expected),
+                            ),
+                        ),
+                    ),
+                );
+            }
+        }
+
+        diagnostics
+    }
+}
+
+pub struct MultipleH1;
+
+impl Rule for MultipleH1 {
+    fn id(&self) -> &str {
+        "multiple-h1"
+    }
+
+    fn default_level(&self) -> DiagnosticLevel {
+        DiagnosticLevel::Warning
+    }
+
+    fn description(&self) -> &str {
+        "File has more than one top-level heading"
+    }
+
+    fn explanation(&self) -> &str {
+        "A memory file should have a single `#` title with everything else nested below it, so \
+         each part of the file has one clear scope."
+    }
+
+    fn enabled_by_default(&self) -> bool {
+        false
+    }
+
+    fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for file in ctx.files {
+            let headings = headings(file);
+            let mut top_level = headings.iter().filter(|h| h.level == 1);
+            let Some(first) = top_level.next() else {
+                continue;
+            };
+            for heading in top_level {
+                diagnostics.push(
+                    Diagnostic::new(
+                        self.default_level(),
+                        self.id(),
+                        format!(
+                            "Another top-level heading \"{}\"; the first is on line {}",
+                            heading.title, first.line
+                        ),
+                    )
+                    .with_file(&file.path)
+                    .with_line(heading.line),
+                );
+            }
+        }
+
+        diagnostics
+    }
+}
+
+pub struct DuplicateHeading;
+
+impl Rule for DuplicateHeading {
+    fn id(&self) -> &str {
+        "duplicate-heading"
+    }
+
+    fn default_level(&self) -> DiagnosticLevel {
+        DiagnosticLevel::Warning
+    }
+
+    fn description(&self) -> &str {
+        "Heading title appears more than once in the file"
+    }
+
+    fn explanation(&self) -> &str {
+        "Headings with the same title share an anchor, so a `file.md#section` import always \
+         selects the first of them. Give each section a distinct title."
+    }
+
+    fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for file in ctx.files {
+            let mut seen: HashMap<String, usize> = HashMap::new();
+            for heading in headings(file) {
+                let slug = slugify(heading.title);
+                if slug.is_empty() {
+                    continue;
+                }
+                match seen.get(&slug) {
+                    Some(first) => diagnostics.push(
+                        Diagnostic::new(
+                            self.default_level(),
+                            self.id(),
+                            format!(
+                                "Duplicate heading \"{}\"; first used on line {}",
+                                heading.title, first
+                            ),
+                        )
+                        .with_file(&file.path)
+                        .with_line(heading.line),
+                    ),
+                    None => {
+                        seen.insert(slug, heading.line);
+                    }
+                }
+            }
+        }
+
+        diagnostics
+    }
+}
+
+pub struct EmptySection;
+
+impl Rule for EmptySection {
+    fn id(&self) -> &str {
+        "empty-section"
+    }
+
+    fn default_level(&self) -> DiagnosticLevel {
+        DiagnosticLevel::Info
+    }
+
+    fn description(&self) -> &str {
+        "Section has a heading but no content"
+    }
+
+    fn explanation(&self) -> &str {
+        "A heading followed directly by another heading of the same or a higher level, or by the \
+         end of the file, adds tokens without giving any instruction. Fill in the section or \
+         remove the heading."
+    }
+
+    fn enabled_by_default(&self) -> bool {
+        false
+    }
+
+    fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for file in ctx.files {
+            let lines = lines(file);
+            let headings = headings(file);
+
+            for (idx, heading) in headings.iter().enumerate() {
+                let next = headings.get(idx + 1);
+                if next.is_some_and(|next| next.level > heading.level) {
+                    continue;
+                }
+                let end = next.map_or(lines.len(), |next| next.line - 1);
+                let has_content = lines[heading.line..end]
+                    .iter()
+                    .any(|(_, _, text, _)| !text.trim().is_empty());
+                if has_content {
+                    continue;
+                }
+
+                diagnostics.push(
+                    Diagnostic::new(
+                        self.default_level(),
+                        self.id(),
+                        format!("Section \"{}\" is empty", heading.title),
+                    )
+                    .with_file(&file.path)
+                    .with_line(heading.line),
+                );
+            }
+        }
+
+        diagnostics
+    }
+}
+
+pub struct LargeSection;
+
+impl Rule for LargeSection {
+    fn id(&self) -> &str {
+        "large-section"
+    }
+
+    fn default_level(&self) -> DiagnosticLevel {
+        DiagnosticLevel::Warning
+    }
+
+    fn description(&self) -> &str {
+        "Section is too long"
+    }
+
+    fn explanation(&self) -> &str {
+        "Sections over 200 lines or about 3000 tokens, not counting their subsections, are hard to \
+         keep consistent and are loaded in full even when only part of them is relevant. Split \
+         them into subsections or move them into a file imported where needed."
+    }
+
+    fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for file in ctx.files {
+            let lines = lines(file);
+            let headings = headings(file);
+
+            for (idx, heading) in headings.iter().enumerate() {
+                let end = headings
+                    .get(idx + 1)
+                    .map_or(lines.len(), |next| next.line - 1);
+                let section = &lines[heading.line - 1..end];
+                let line_count = section.len();
+                let tokens: usize = section
+                    .iter()
+                    .map(|(_, _, text, _)| estimate_tokens(text))
+                    .sum();
+                if line_count <= MAX_SECTION_LINES && tokens <= MAX_SECTION_TOKENS {
+                    continue;
+                }
+
+                diagnostics.push(
+                    Diagnostic::new(
+                        self.default_level(),
+                        self.id(),
+                        format!(
+                            "Section \"{}\" has {} lines (~{} tokens); limits are {} lines and {} tokens",
+                            heading.title, line_count, tokens, MAX_SECTION_LINES, MAX_SECTION_TOKENS
+                        ),
+                    )
+                    .with_file(&file.path)
+                    .with_line(heading.line),
+                );
+            }
+        }
+
+        diagnostics
+    }
+}
+
+pub struct UnterminatedCodeFence;
+
+impl Rule for UnterminatedCodeFence {
+    fn id(&self) -> &str {
+        "unterminated-code-fence"
+    }
+
+    fn default_level(&self) -> DiagnosticLevel {
+        DiagnosticLevel::Warning
+    }
+
+    fn description(&self) -> &str {
+        "Code block is never closed"
+    }
+
+    fn explanation(&self) -> &str {
+        "Without a closing fence, everything after the opening ``` or ~~~ is a code block: \
+         headings stop being sections and imports are not followed. The fix closes the block at \
+         the end of the file."
+    }
+
+    fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for file in ctx.files {
+            let mut fences = Fences::default();
+            for (idx, line) in file.content.lines().enumerate() {
+                fences.update(idx + 1, line);
+            }
+            let Some((line, marker, len)) = fences.open else {
+                continue;
+            };
+
+            let mut closing = String::new();
+            if !file.content.ends_with('\n') {
+                closing.push('\n');
+            }
+            closing.push_str(&marker.to_string().repeat(len));
+            closing.push('\n');
+
+            diagnostics.push(
+                Diagnostic::new(
+                    self.default_level(),
+                    self.id(),
+                    "Code block is not closed before the end of the file",
+                )
+                .with_file(&file.path)
+                .with_line(line)
+                .with_fix(
+                    Fix::new("Close the code block").with_edit(TextEdit::insert(
+                        &file.path,
+                        file.content.len(),
+                        closing,
+                    )),
+                ),
+            );
+        }
+
+        diagnostics
+    }
+}
+
+pub struct TableColumns;
+
+impl Rule for TableColumns {
+    fn id(&self) -> &str {
+        "table-columns"
+    }
+
+    fn default_level(&self) -> DiagnosticLevel {
+        DiagnosticLevel::Warning
+    }
+
+    fn description(&self) -> &str {
+        "Table row has a different number of cells than the header"
+    }
+
+    fn explanation(&self) -> &str {
+        "A table whose delimiter row does not match the header is not rendered as a table at all, \
+         and rows with missing or extra cells are padded or truncated silently. Rows that end \
+         with `|` and are missing cells can be padded with --fix."
+    }
+
+    fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for file in ctx.files {
+            let lines = lines(file);
+            let mut idx = 0;
+
+            while idx + 1 < lines.len() {
+                let (_, _, header, header_in_code) = lines[idx];
+                let (_, delimiter_line, delimiter, delimiter_in_code) = lines[idx + 1];
+                let is_table = !header_in_code
+                    && !delimiter_in_code
+                    && header.contains('|')
+                    && delimiter.contains('|')
+                    && TABLE_DELIMITER_REGEX.is_match(delimiter);
+                if !is_table {
+                    idx += 1;
+                    continue;
+                }
+
+                let columns = table_cells(header);
+                let delimiter_cells = table_cells(delimiter);
+                if delimiter_cells != columns {
+                    diagnostics.push(
+                        Diagnostic::new(
+                            self.default_level(),
+                            self.id(),
+                            format!(
+                                "Table delimiter row has {} cells but the header has {}; the table will not render",
+                                delimiter_cells, columns
+                            ),
+                        )
+                        .with_file(&file.path)
+                        .with_line(delimiter_line),
+                    );
+                }
+
+                idx += 2;
+                while let Some(&(offset, line_number, row, in_code)) = lines.get(idx) {
+                    if in_code || row.trim().is_empty() || !row.contains('|') {
+                        break;
+                    }
+                    idx += 1;
+
+                    let cells = table_cells(row);
+                    if cells == columns {
+                        continue;
+                    }
+                    let mut diagnostic = Diagnostic::new(
+                        self.default_level(),
+                        self.id(),
+                        format!(
+                            "Table row has {} cells but the header has {}",
+                            cells, columns
+                        ),
+                    )
+                    .with_file(&file.path)
+                    .with_line(line_number);
+
+                    let trimmed = row.trim_end();
+                    if cells < columns && trimmed.ends_with('|') && !trimmed.ends_with("\\|") {
+                        diagnostic = diagnostic.with_fix(Fix::new("Add empty cells").with_edit(
+                            TextEdit::replace(
+                                &file.path,
+                                offset + trimmed.len(),
+                                offset + row.len(),
+                                "  |".repeat(columns - cells),
+                            ),
+                        ));
+                    }
+                    diagnostics.push(diagnostic);
+                }
+            }
+        }
+
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Config, ImportGraph, MemoryType, Parser};
+    use std::path::Path;
+
+    fn check(rule: &dyn Rule, content: &str) -> Vec<Diagnostic> {
+        let files = [MemoryFile::from_content(
+            "/repo/CLAUDE.md",
+            MemoryType::ProjectMemory,
+            content,
+        )];
+        let (graph, config) = (ImportGraph::new(), Config::default());
+        rule.check(&RuleContext {
+            files: &files,
+            graph: &graph,
+            config: &config,
+            project_root: Path::new("/repo"),
+            parser: &Parser::new(),
+        })
+    }
+
+    /// `content` with the fixes of every diagnostic applied.
+    fn fixed(rule: &dyn Rule, content: &str) -> String {
+        let mut edits: Vec<TextEdit> = check(rule, content)
+            .into_iter()
+            .filter_map(|d| d.fix)
+            .flat_map(|fix| fix.edits)
+            .collect();
+        edits.sort_by_key(|edit| std::cmp::Reverse(edit.start));
+        let mut content = content.to_string();
+        for edit in edits {
+            content.replace_range(edit.start..edit.end, &edit.replacement);
+        }
+        content
+    }
+
+    #[test]
+    fn heading_space_fixes_titles_only() {
+        let content = "#Title\n##setup\n#123 was fixed upstream.\n#hashtag\n```\n#Code\n```\n";
+        let diagnostics = check(&HeadingSpace, content);
+        let lines: Vec<_> = diagnostics.iter().filter_map(|d| d.line).collect();
+        assert_eq!(lines, vec![1, 2]);
+        assert_eq!(
+            fixed(&HeadingSpace, content),
+            "# Title\n## setup\n#123 was fixed upstream.\n#hashtag\n```\n#Code\n```\n"
+        );
+    }
+
+    #[test]
+    fn trailing_whitespace_keeps_line_breaks() {
+        let content = "Text  \nTabs\t \r\n  \n";
+        let lines: Vec<_> = check(&TrailingWhitespace, content)
+            .iter()
+            .filter_map(|d| Some((d.line?, d.column?)))
+            .collect();
+        assert_eq!(lines, vec![(2, 5), (3, 1)]);
+        assert_eq!(fixed(&TrailingWhitespace, content), "Text  \nTabs\r\n\n");
+    }
+
+    #[test]
+    fn trailing_whitespace_skips_fenced_code() {
+        let content = "Text \n```\ncode  \n\t\n```\n~~~ sh\nmake \n~~~\nEnd\t\n";
+        let lines: Vec<_> = check(&TrailingWhitespace, content)
+            .iter()
+            .filter_map(|d| d.line)
+            .collect();
+        assert_eq!(lines, vec![1, 9]);
+        assert_eq!(
+            fixed(&TrailingWhitespace, content),
+            "Text\n```\ncode  \n\t\n```\n~~~ sh\nmake \n~~~\nEnd\n"
+        );
+    }
+
+    #[test]
+    fn final_newline_is_appended() {
+        assert_eq!(fixed(&FinalNewline, "# Title\nText"), "# Title\nText\n");
+        assert!(check(&FinalNewline, "# Title\n").is_empty());
+        assert!(check(&FinalNewline, "").is_empty());
+    }
+
+    #[test]
+    fn heading_increment_lowers_skipped_levels() {
+        let content = "# Title\n### Setup\n## Usage\n#### Flags\n```\n###### Code\n```\n";
+        let lines: Vec<_> = check(&HeadingIncrement, content)
+            .iter()
+            .filter_map(|d| d.line)
+            .collect();
+        assert_eq!(lines, vec![2, 4]);
+        assert_eq!(
+            fixed(&HeadingIncrement, content),
+            "# Title\n## Setup\n## Usage\n### Flags\n```\n###### Code\n```\n"
+        );
+    }
+
+    #[test]
+    fn unterminated_code_fence_is_closed_with_its_marker() {
+        assert_eq!(
+            fixed(&UnterminatedCodeFence, "# Title\n~~~~\ncode\n```"),
+            "# Title\n~~~~\ncode\n```\n~~~~\n"
+        );
+        assert!(check(&UnterminatedCodeFence, "````\n```\n````\n").is_empty());
+    }
+
+    #[test]
+    fn table_rows_missing_cells_are_padded() {
+        let content = "| a | b | c |\n|---|---|---|\n| 1 |\n| 1 | 2 | 3 | 4 |\n| 1 | 2 \\|\n";
+        let lines: Vec<_> = check(&TableColumns, content)
+            .iter()
+            .filter_map(|d| d.line)
+            .collect();
+        assert_eq!(lines, vec![3, 4, 5]);
+        assert_eq!(
+            fixed(&TableColumns, content),
+            "| a | b | c |\n|---|---|---|\n| 1 |  |  |\n| 1 | 2 | 3 | 4 |\n| 1 | 2 \\|\n"
+        );
+        assert_eq!(
+            check(&TableColumns, "| a | b |\n|---|\n").len(),
+            1,
+            "mismatched delimiter row"
+        );
+    }
+
+    #[test]
+    fn heading_structure_rules_report_lines() {
+        let lines = |rule: &dyn Rule, content: &str| -> Vec<usize> {
+            check(rule, content).iter().filter_map(|d| d.line).collect()
+        };
+        let content = "# Title\nIntro\n## Setup\n## Usage\nRun it\n## setup\nMore\n# Other\n";
+        assert_eq!(lines(&MultipleH1, content), vec![8]);
+        assert_eq!(lines(&DuplicateHeading, content), vec![6]);
+        assert_eq!(lines(&EmptySection, content), vec![3, 8]);
+
+        let large = format!("# Title\n## Big\n{}## Small\nText\n", "line\n".repeat(201));
+        assert_eq!(lines(&LargeSection, &large), vec![2]);
+    }
+}
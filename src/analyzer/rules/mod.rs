@@ -0,0 +1,167 @@
+pub mod content;
+pub mod custom;
+pub mod files;
+pub mod imports;
+pub mod markdown;
+pub mod script;
+pub mod security;
+pub mod suppressions;
+
+use super::{Diagnostic, DiagnosticLevel};
+use crate::core::resolver::expand_home;
+use crate::core::{Config, ImportGraph, MemoryFile, Parser};
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
+
+/// Everything a rule may inspect. All data is read-only.
+pub struct RuleContext<'a> {
+    pub files: &'a [MemoryFile],
+    pub graph: &'a ImportGraph,
+    pub config: &'a Config,
+    pub project_root: &'a Path,
+    /// Parser shared by all rules, so files are parsed once per run.
+    pub parser: &'a Parser,
+}
+
+impl RuleContext<'_> {
+    /// Path of the scanned file behind a canonical graph node, so that
+    /// diagnostics name files the way the scanner found them; nodes reached
+    /// only through imports keep their canonical path.
+    pub fn scanned_path(&self, node: &Path) -> PathBuf {
+        self.files
+            .iter()
+            .find(|file| file.path == node || file.path.canonicalize().is_ok_and(|c| c == node))
+            .map_or_else(|| node.to_path_buf(), |file| file.path.clone())
+    }
+}
+
+/// A single diagnostic check.
+///
+/// Diagnostics returned from `check` should carry the rule's id as their
+/// `code`; the engine applies configured severity overrides afterwards.
+pub trait Rule: Send + Sync {
+    fn id(&self) -> &str;
+
+    fn default_level(&self) -> DiagnosticLevel;
+
+    /// One-line summary shown by `ccat rules list`.
+    fn description(&self) -> &str;
+
+    /// Longer text shown by `ccat rules explain`.
+    fn explanation(&self) -> &str {
+        self.description()
+    }
+
+    /// Opt-in rules only run when enabled explicitly or when all rules run.
+    fn enabled_by_default(&self) -> bool {
+        true
+    }
+
+    fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic>;
+}
+
+#[derive(Default)]
+pub struct RuleRegistry {
+    rules: Vec<Box<dyn Rule>>,
+}
+
+impl RuleRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registry containing every built-in rule.
+    pub fn builtin() -> Self {
+        let mut registry = Self::new();
+        registry.register(Box::new(files::LargeFile));
+        registry.register(Box::new(files::DeprecatedLocalMemory));
+        registry.register(Box::new(imports::MissingImport));
+        registry.register(Box::new(imports::InvalidImportSelector));
+        registry.register(Box::new(imports::ImportPolicyViolation));
+        registry.register(Box::new(imports::EmptyGlobImport));
+        registry.register(Box::new(imports::InvalidGlobImport));
+        registry.register(Box::new(imports::DuplicateGlobImport));
+        registry.register(Box::new(imports::MissingAliasTarget));
+        registry.register(Box::new(imports::CircularImport));
+        registry.register(Box::new(imports::DeepImportChain));
+        registry.register(Box::new(content::DuplicateInstruction));
+        registry.register(Box::new(content::ContradictoryInstruction));
+        registry.register(Box::new(content::StaleReference));
+        registry.register(Box::new(content::ContextBudget));
+        registry.register(Box::new(security::Secret));
+        registry.register(Box::new(markdown::TrailingWhitespace));
+        registry.register(Box::new(markdown::FinalNewline));
+        registry.register(Box::new(markdown::HeadingSpace));
+        registry.register(Box::new(markdown::HeadingIncrement));
+        registry.register(Box::new(markdown::MultipleH1));
+        registry.register(Box::new(markdown::DuplicateHeading));
+        registry.register(Box::new(markdown::EmptySection));
+        registry.register(Box::new(markdown::LargeSection));
+        registry.register(Box::new(markdown::UnterminatedCodeFence));
+        registry.register(Box::new(markdown::TableColumns));
+        registry.register(Box::new(suppressions::UnusedSuppression));
+        registry
+    }
+
+    /// Built-in rules plus the custom rules defined in the `[diagnostics]`
+    /// config and in `rule_files`, and the scripted rules from the config.
+    /// Custom rules may not reuse existing ids.
+    pub fn with_custom_rules(
+        config: &Config,
+        project_root: &Path,
+        rule_files: &[PathBuf],
+    ) -> Result<Self> {
+        let mut rules: Vec<Box<dyn Rule>> = Vec::new();
+        for spec in &config.diagnostics.rules {
+            let rule =
+                custom::CustomRule::new(spec.clone()).with_context(|| match &config.path {
+                    Some(path) => format!("Invalid [[diagnostics.rules]] in {}", path.display()),
+                    None => "Invalid [[diagnostics.rules]]".to_string(),
+                })?;
+            rules.push(Box::new(rule));
+        }
+        let configured = config
+            .diagnostics
+            .rule_files
+            .iter()
+            .map(|file| project_root.join(expand_home(file)));
+        for path in configured.chain(rule_files.iter().cloned()) {
+            for rule in custom::load_file(&path)? {
+                rules.push(Box::new(rule));
+            }
+        }
+        for spec in &config.diagnostics.scripts {
+            rules.push(Box::new(script::ScriptRule::load(
+                spec.clone(),
+                project_root,
+            )?));
+        }
+
+        let mut registry = Self::builtin();
+        for rule in rules {
+            if registry.contains(rule.id()) {
+                bail!("Custom rule {} conflicts with an existing rule", rule.id());
+            }
+            registry.register(rule);
+        }
+        Ok(registry)
+    }
+
+    /// Adds a rule, replacing any existing rule with the same id.
+    pub fn register(&mut self, rule: Box<dyn Rule>) {
+        self.rules.retain(|r| r.id() != rule.id());
+        self.rules.push(rule);
+    }
+
+    pub fn get(&self, id: &str) -> Option<&dyn Rule> {
+        self.rules.iter().find(|r| r.id() == id).map(|r| r.as_ref())
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.get(id).is_some()
+    }
+
+    pub fn rules(&self) -> impl Iterator<Item = &dyn Rule> {
+        self.rules.iter().map(|r| r.as_ref())
+    }
+}
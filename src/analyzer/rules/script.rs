@@ -0,0 +1,374 @@
+use super::custom::check_id;
+use super::{Rule, RuleContext};
+use crate::analyzer::{Diagnostic, DiagnosticLevel};
+use crate::core::parser::Parser;
+use crate::core::resolver::expand_home;
+use crate::core::{MemoryFile, MemoryType};
+use anyhow::{anyhow, bail, Context, Result};
+use rhai::module_resolvers::DummyModuleResolver;
+use rhai::{Array, Dynamic, Engine, EvalAltResult, Map, Scope, AST};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Bounds a runaway script instead of hanging the run
+const MAX_OPERATIONS: u64 = 50_000_000;
+
+// Bound memory use; strings must still hold the largest memory files
+const MAX_STRING_SIZE: usize = 10_000_000;
+const MAX_ARRAY_SIZE: usize = 1_000_000;
+const MAX_MAP_SIZE: usize = 100_000;
+const MAX_CALL_LEVELS: usize = 64;
+
+const ENTRY_POINT: &str = "check";
+
+/// A scripted rule as configured with `[[diagnostics.scripts]]`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScriptRuleSpec {
+    pub id: String,
+    /// Rhai script defining `fn check(ctx)`, relative to the project root.
+    pub path: String,
+    #[serde(default = "default_severity")]
+    pub severity: DiagnosticLevel,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Only run with `--all-rules` or `--enable`.
+    #[serde(default)]
+    pub opt_in: bool,
+}
+
+fn default_severity() -> DiagnosticLevel {
+    DiagnosticLevel::Warning
+}
+
+/// Rule implemented by a Rhai script. The script gets a copy of the scanned
+/// files, their sections and imports, and the import graph; it has no access
+/// to the filesystem, cannot load modules and is stopped after
+/// `MAX_OPERATIONS` steps or when it exceeds the size and call depth limits.
+pub struct ScriptRule {
+    spec: ScriptRuleSpec,
+    path: PathBuf,
+    description: String,
+    engine: Engine,
+    ast: AST,
+}
+
+impl ScriptRule {
+    pub fn load(spec: ScriptRuleSpec, project_root: &Path) -> Result<Self> {
+        check_id(&spec.id)?;
+        let path = project_root.join(expand_home(&spec.path));
+        let source = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read script: {}", path.display()))?;
+
+        let engine = sandboxed_engine(&spec.id);
+        let ast = engine
+            .compile(&source)
+            .map_err(|err| anyhow!("{}", err))
+            .with_context(|| format!("Failed to compile script: {}", path.display()))?;
+
+        let has_entry_point = ast
+            .iter_functions()
+            .any(|f| f.name == ENTRY_POINT && f.params.len() == 1);
+        if !has_entry_point {
+            bail!(
+                "Script {} must define `fn {}(ctx)`",
+                path.display(),
+                ENTRY_POINT
+            );
+        }
+
+        let description = spec
+            .description
+            .clone()
+            .unwrap_or_else(|| format!("Scripted rule {}", spec.path));
+
+        Ok(Self {
+            spec,
+            path,
+            description,
+            engine,
+            ast,
+        })
+    }
+
+    /// Reports a script failure at the failing line of the script itself.
+    fn script_error(&self, message: String, line: Option<usize>) -> Diagnostic {
+        let diagnostic =
+            Diagnostic::new(DiagnosticLevel::Error, self.id(), message).with_file(&self.path);
+        match line {
+            Some(line) => diagnostic.with_line(line),
+            None => diagnostic,
+        }
+    }
+
+    fn to_diagnostic(&self, value: Dynamic) -> Result<Diagnostic> {
+        if value.is_string() {
+            let message = value.into_string().map_err(|t| anyhow!("{}", t))?;
+            return Ok(Diagnostic::new(self.spec.severity, self.id(), message));
+        }
+
+        let Some(map) = value.try_cast::<Map>() else {
+            bail!("expected a message string or a map");
+        };
+        let text = |key: &str| -> Result<Option<String>> {
+            match map.get(key) {
+                None => Ok(None),
+                Some(v) if v.is_unit() => Ok(None),
+                Some(v) => v
+                    .clone()
+                    .into_string()
+                    .map(Some)
+                    .map_err(|t| anyhow!("`{}` must be a string, not {}", key, t)),
+            }
+        };
+        let number = |key: &str| -> Result<Option<usize>> {
+            match map.get(key) {
+                None => Ok(None),
+                Some(v) if v.is_unit() => Ok(None),
+                Some(v) => match v.as_int() {
+                    Ok(n) if n >= 1 => Ok(Some(n as usize)),
+                    _ => bail!("`{}` must be a positive integer", key),
+                },
+            }
+        };
+
+        let Some(message) = text("message")? else {
+            bail!("missing `message`");
+        };
+        let mut diagnostic = Diagnostic::new(self.spec.severity, self.id(), message);
+        if let Some(file) = text("file")? {
+            diagnostic = diagnostic.with_file(file);
+        }
+        if let Some(line) = number("line")? {
+            diagnostic = diagnostic.with_line(line);
+        }
+        if let Some(column) = number("column")? {
+            diagnostic = diagnostic.with_column(column);
+        }
+        Ok(diagnostic)
+    }
+}
+
+impl Rule for ScriptRule {
+    fn id(&self) -> &str {
+        &self.spec.id
+    }
+
+    fn default_level(&self) -> DiagnosticLevel {
+        self.spec.severity
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn enabled_by_default(&self) -> bool {
+        !self.spec.opt_in
+    }
+
+    fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        let result: Result<Dynamic, Box<EvalAltResult>> =
+            self.engine
+                .call_fn(&mut Scope::new(), &self.ast, ENTRY_POINT, (context(ctx),));
+
+        let value = match result {
+            Ok(value) => value,
+            Err(err) => {
+                let line = err.position().line();
+                return vec![self.script_error(format!("Script failed: {}", err), line)];
+            }
+        };
+        if value.is_unit() {
+            return Vec::new();
+        }
+        let Some(items) = value.try_cast::<Array>() else {
+            return vec![self.script_error(
+                format!("`{}` must return an array of diagnostics", ENTRY_POINT),
+                None,
+            )];
+        };
+
+        items
+            .into_iter()
+            .enumerate()
+            .map(|(idx, item)| {
+                self.to_diagnostic(item).unwrap_or_else(|err| {
+                    self.script_error(format!("Invalid diagnostic #{}: {}", idx + 1, err), None)
+                })
+            })
+            .collect()
+    }
+}
+
+fn sandboxed_engine(id: &str) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_module_resolver(DummyModuleResolver::new());
+    engine.disable_symbol("eval");
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine.set_max_string_size(MAX_STRING_SIZE);
+    engine.set_max_array_size(MAX_ARRAY_SIZE);
+    engine.set_max_map_size(MAX_MAP_SIZE);
+    engine.set_max_call_levels(MAX_CALL_LEVELS);
+    // Rhai's defaults differ between debug and release builds
+    engine.set_max_expr_depths(64, 64);
+
+    let prefix = id.to_string();
+    engine.on_print(move |text| eprintln!("[{}] {}", prefix, text));
+    let prefix = id.to_string();
+    engine.on_debug(move |text, _, position| eprintln!("[{}] {:?} {}", prefix, position, text));
+    engine
+}
+
+/// Copy of the rule context handed to scripts.
+fn context(ctx: &RuleContext) -> Map {
+    let mut graph = Array::new();
+    for from in ctx.graph.nodes() {
+        for to in ctx.graph.dependencies(from) {
+            let mut edge = Map::new();
+            edge.insert("from".into(), path_value(from));
+            edge.insert("to".into(), path_value(to));
+            edge.insert(
+                "line".into(),
+                line_value(ctx.graph.import_line(from, to).unwrap_or(0)),
+            );
+            graph.push(edge.into());
+        }
+    }
+
+    let mut map = Map::new();
+    map.insert(
+        "files".into(),
+        ctx.files
+            .iter()
+            .map(|file| file_value(file, ctx.project_root, ctx.parser).into())
+            .collect::<Array>()
+            .into(),
+    );
+    map.insert("graph".into(), graph.into());
+    map.insert("project_root".into(), path_value(ctx.project_root));
+    map
+}
+
+fn file_value(file: &MemoryFile, project_root: &Path, parser: &Parser) -> Map {
+    let relative = file.path.strip_prefix(project_root).unwrap_or(&file.path);
+    let file_type = match file.file_type {
+        MemoryType::ProjectMemory => "project",
+        MemoryType::UserMemory => "user",
+        MemoryType::LocalMemory => "local",
+        MemoryType::SubdirMemory => "subdir",
+    };
+
+    let sections: Array = parser
+        .parse(file)
+        .map(|parsed| parsed.sections)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|section| {
+            let mut map = Map::new();
+            map.insert("title".into(), section.title.into());
+            map.insert("level".into(), line_value(section.level));
+            map.insert("line_start".into(), line_value(section.line_start));
+            map.insert("line_end".into(), line_value(section.line_end));
+            map.insert("content".into(), section.content.into());
+            map.into()
+        })
+        .collect();
+
+    let imports: Array = file
+        .imports
+        .iter()
+        .map(|import| {
+            let mut map = Map::new();
+            map.insert("path".into(), import.path.clone().into());
+            map.insert("line".into(), line_value(import.line_number));
+            map.insert(
+                "resolved".into(),
+                import
+                    .targets()
+                    .map(|p| path_value(p))
+                    .collect::<Array>()
+                    .into(),
+            );
+            map.into()
+        })
+        .collect();
+
+    let mut map = Map::new();
+    map.insert("path".into(), path_value(&file.path));
+    map.insert("relative".into(), path_value(relative));
+    map.insert(
+        "dir".into(),
+        path_value(relative.parent().unwrap_or(Path::new(""))),
+    );
+    map.insert(
+        "canonical".into(),
+        path_value(
+            &file
+                .path
+                .canonicalize()
+                .unwrap_or_else(|_| file.path.clone()),
+        ),
+    );
+    map.insert("type".into(), file_type.into());
+    map.insert("content".into(), file.content.clone().into());
+    map.insert(
+        "lines".into(),
+        file.content
+            .lines()
+            .map(|line| Dynamic::from(line.to_string()))
+            .collect::<Array>()
+            .into(),
+    );
+    map.insert("sections".into(), sections.into());
+    map.insert("imports".into(), imports.into());
+    map
+}
+
+fn path_value(path: &Path) -> Dynamic {
+    path.to_string_lossy().to_string().into()
+}
+
+fn line_value(line: usize) -> Dynamic {
+    (line as i64).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Config, ImportGraph};
+
+    fn run(source: &str) -> Vec<Diagnostic> {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("rule.rhai"), source).unwrap();
+        let spec = ScriptRuleSpec {
+            id: "script".to_string(),
+            path: "rule.rhai".to_string(),
+            severity: DiagnosticLevel::Warning,
+            description: None,
+            opt_in: false,
+        };
+        let rule = ScriptRule::load(spec, dir.path()).unwrap();
+        let (graph, config) = (ImportGraph::new(), Config::default());
+        rule.check(&RuleContext {
+            files: &[],
+            graph: &graph,
+            config: &config,
+            project_root: dir.path(),
+            parser: &Parser::new(),
+        })
+    }
+
+    #[test]
+    fn stops_scripts_that_grow_without_bound() {
+        for source in [
+            "fn check(ctx) { let s = \"x\"; loop { s += s; } }",
+            "fn check(ctx) { let a = [0]; loop { a += a; } }",
+            "fn deeper(n) { deeper(n + 1) }\nfn check(ctx) { deeper(0) }",
+        ] {
+            let diagnostics = run(source);
+            assert_eq!(diagnostics.len(), 1, "{}", source);
+            assert_eq!(diagnostics[0].level, DiagnosticLevel::Error);
+        }
+    }
+}
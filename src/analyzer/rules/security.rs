@@ -0,0 +1,53 @@
+use super::{Rule, RuleContext};
+use crate::analyzer::security::SecurityScanner;
+use crate::analyzer::{Diagnostic, DiagnosticLevel};
+
+pub struct Secret;
+
+impl Rule for Secret {
+    fn id(&self) -> &str {
+        "secret"
+    }
+
+    fn default_level(&self) -> DiagnosticLevel {
+        DiagnosticLevel::Error
+    }
+
+    fn description(&self) -> &str {
+        "Memory file or imported file contains a credential"
+    }
+
+    fn explanation(&self) -> &str {
+        "Memory files and everything they import are sent to the model in every conversation \
+         and are usually committed. Known credential formats are detected (private key \
+         blocks, AWS, Google, Azure, GitHub, GitLab, Slack, Stripe, Anthropic, OpenAI and npm \
+         keys, JSON web tokens, passwords in connection strings and `password = ...` style \
+         assignments), as well as long mixed-case tokens with digits and high Shannon \
+         entropy. Values that look like placeholders, environment variable references or \
+         file paths are skipped, as are CamelCase identifiers and `sha512-` integrity \
+         hashes. Values are masked in the report; rotate any real credential and reference \
+         it from the environment instead."
+    }
+
+    fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        SecurityScanner::new()
+            .find(ctx.files, ctx.graph)
+            .into_iter()
+            .map(|secret| {
+                let source = if secret.imported {
+                    " in imported file"
+                } else {
+                    ""
+                };
+                Diagnostic::new(
+                    self.default_level(),
+                    self.id(),
+                    format!("Possible {}{}: {}", secret.kind, source, secret.masked),
+                )
+                .with_file(&secret.file)
+                .with_line(secret.line)
+                .with_column(secret.column)
+            })
+            .collect()
+    }
+}
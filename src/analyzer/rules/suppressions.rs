@@ -0,0 +1,34 @@
+use super::{Rule, RuleContext};
+use crate::analyzer::{Diagnostic, DiagnosticLevel};
+
+pub const UNUSED_SUPPRESSION: &str = "unused-suppression";
+
+/// Reported by `Diagnostics` itself once every other rule has run, since
+/// only then is it known which suppressions were used.
+pub struct UnusedSuppression;
+
+impl Rule for UnusedSuppression {
+    fn id(&self) -> &str {
+        UNUSED_SUPPRESSION
+    }
+
+    fn default_level(&self) -> DiagnosticLevel {
+        DiagnosticLevel::Warning
+    }
+
+    fn description(&self) -> &str {
+        "Suppression comment does not silence any diagnostic"
+    }
+
+    fn explanation(&self) -> &str {
+        "Diagnostics can be silenced with `<!-- ccat-disable-next-line rule -->`, with a \
+         `<!-- ccat-disable rule -->` ... `<!-- ccat-enable -->` region, or for a whole file \
+         with `ccat-disable: [rule]` in the frontmatter. Without rule ids every rule is \
+         silenced. A suppression that silences nothing, or names an unknown rule, is \
+         reported so stale suppressions do not pile up."
+    }
+
+    fn check(&self, _ctx: &RuleContext) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+}
@@ -0,0 +1,567 @@
+use crate::core::MemoryFile;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+static INLINE_CODE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"`([^`]+)`").unwrap());
+
+static PATH_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(?:\.{1,2}/)?[\w.-]+(?:/[\w.-]+)*/?$").unwrap());
+
+static MAKE_TARGET_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^([A-Za-z0-9_./%-]+(?:\s+[A-Za-z0-9_./%-]+)*)\s*::?(.?)").unwrap());
+
+static JUST_RECIPE_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^@?([A-Za-z_][\w-]*)(?:\s[^:]*)?:(.?)").unwrap());
+
+// Build output and dependency directories rarely exist in a fresh checkout.
+const GENERATED_DIRS: &[&str] = &[
+    "target",
+    "dist",
+    "build",
+    "out",
+    "node_modules",
+    "coverage",
+    ".git",
+];
+
+const MAKEFILES: &[&str] = &["GNUmakefile", "makefile", "Makefile"];
+
+/// A path or build command mentioned in a memory file that no longer exists.
+#[derive(Debug, Clone)]
+pub struct StaleReference {
+    pub file: PathBuf,
+    pub line: usize,
+    pub reference: String,
+    pub reason: String,
+}
+
+pub struct StalenessChecker {
+    project_root: PathBuf,
+}
+
+impl StalenessChecker {
+    pub fn new<P: AsRef<Path>>(project_root: P) -> Self {
+        Self {
+            project_root: project_root.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Checks path-like tokens against the file system, and `make`, `just`,
+    /// package manager and `cargo` invocations found in code against the
+    /// manifests of the memory file's directory and the project root.
+    pub fn find(&self, files: &[MemoryFile]) -> Vec<StaleReference> {
+        let mut manifests: HashMap<PathBuf, Manifests> = HashMap::new();
+        let mut stale = Vec::new();
+
+        for file in files {
+            let file_dir = file.path.parent().unwrap_or(Path::new("")).to_path_buf();
+            let mut dirs = vec![file_dir];
+            if !dirs.contains(&self.project_root) {
+                dirs.push(self.project_root.clone());
+            }
+            for dir in &dirs {
+                manifests
+                    .entry(dir.clone())
+                    .or_insert_with(|| Manifests::load(dir));
+            }
+            let file_manifests: Vec<&Manifests> = dirs.iter().map(|d| &manifests[d]).collect();
+
+            for (idx, fragment) in fragments(&file.content) {
+                let mut push = |reference: String, reason: String| {
+                    stale.push(StaleReference {
+                        file: file.path.clone(),
+                        line: idx + 1,
+                        reference,
+                        reason,
+                    });
+                };
+
+                for token in fragment.text.split_whitespace() {
+                    if let Some(path) = path_token(token) {
+                        if !dirs.iter().any(|dir| dir.join(path).exists()) {
+                            push(path.to_string(), "path does not exist".to_string());
+                        }
+                    }
+                }
+
+                if fragment.is_code {
+                    for command in commands(fragment.text) {
+                        if let Some(reason) = check_command(&command, &file_manifests) {
+                            push(command.join(" "), reason);
+                        }
+                    }
+                }
+            }
+        }
+
+        stale
+    }
+}
+
+struct Fragment<'a> {
+    text: &'a str,
+    is_code: bool,
+}
+
+/// Splits content into prose and code (fenced blocks and inline code spans),
+/// paired with their 0-based line index.
+fn fragments(content: &str) -> Vec<(usize, Fragment<'_>)> {
+    let mut fragments = Vec::new();
+    let mut in_code_block = false;
+
+    for (idx, line) in content.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            fragments.push((
+                idx,
+                Fragment {
+                    text: line,
+                    is_code: true,
+                },
+            ));
+            continue;
+        }
+        if trimmed.starts_with("{{import") {
+            continue;
+        }
+
+        let mut last = 0;
+        for captures in INLINE_CODE_REGEX.captures_iter(line) {
+            let span = captures.get(0).unwrap();
+            fragments.push((
+                idx,
+                Fragment {
+                    text: &line[last..span.start()],
+                    is_code: false,
+                },
+            ));
+            fragments.push((
+                idx,
+                Fragment {
+                    text: captures.get(1).unwrap().as_str(),
+                    is_code: true,
+                },
+            ));
+            last = span.end();
+        }
+        fragments.push((
+            idx,
+            Fragment {
+                text: &line[last..],
+                is_code: false,
+            },
+        ));
+    }
+
+    fragments
+}
+
+/// A relative path with at least one directory separator that names either
+/// a directory (trailing `/`) or a file with an extension, so prose like
+/// "and/or" or "CI/CD" is not mistaken for a path.
+fn path_token(token: &str) -> Option<&str> {
+    let token = token
+        .trim_matches(|c: char| matches!(c, '"' | '\'' | '(' | ')' | '[' | ']' | '<' | '>'))
+        .trim_end_matches([',', ';', ':', '!', '?']);
+    let token = token.split(['#', ':']).next()?;
+    let token = match token.strip_suffix('.') {
+        Some(stripped) if !stripped.ends_with('/') && !stripped.is_empty() => stripped,
+        _ => token,
+    };
+
+    if !PATH_REGEX.is_match(token) || !token.contains('/') {
+        return None;
+    }
+    let has_extension = token
+        .rsplit('/')
+        .next()
+        .is_some_and(|name| name.trim_start_matches('.').contains('.'));
+    if !token.ends_with('/') && !has_extension {
+        return None;
+    }
+
+    let first = token
+        .trim_start_matches("./")
+        .split('/')
+        .next()
+        .unwrap_or_default();
+    // `github.com/org/repo.git` and the like
+    let is_domain = first.contains('.') && !first.starts_with('.');
+    if GENERATED_DIRS.contains(&first) || is_domain || token.starts_with("../") {
+        return None;
+    }
+
+    Some(token)
+}
+
+/// Commands in a code fragment, split at shell separators, with a leading
+/// prompt (`$`) and environment assignments dropped.
+fn commands(text: &str) -> Vec<Vec<&str>> {
+    text.split(['&', '|', ';'])
+        .map(|part| {
+            part.split_whitespace()
+                .skip_while(|word| *word == "$" || word.contains('='))
+                .collect::<Vec<_>>()
+        })
+        .filter(|words| !words.is_empty())
+        .collect()
+}
+
+fn check_command(words: &[&str], manifests: &[&Manifests]) -> Option<String> {
+    let args: Vec<&str> = words[1..].to_vec();
+
+    match words[0] {
+        "make" => {
+            let mut directory = None;
+            let mut makefile = None;
+            let mut targets = Vec::new();
+            let mut args = args.into_iter().peekable();
+            while let Some(arg) = args.next() {
+                match arg {
+                    "-C" | "--directory" => directory = args.next(),
+                    "-f" | "--file" | "--makefile" => makefile = args.next(),
+                    "-I" | "--include-dir" | "-o" | "--old-file" | "--assume-old" | "-W"
+                    | "--what-if" | "--new-file" | "--assume-new" => {
+                        args.next();
+                    }
+                    // The job and load limits are optional
+                    "-j" | "--jobs" | "-l" | "--load-average" => {
+                        args.next_if(|value| value.parse::<f64>().is_ok());
+                    }
+                    _ if arg.starts_with('-') || arg.contains('=') => {}
+                    _ => targets.push(arg),
+                }
+            }
+            if targets.is_empty() {
+                return None;
+            }
+
+            let loaded: Vec<BTreeSet<String>>;
+            let known: Vec<&BTreeSet<String>> = if directory.is_some() || makefile.is_some() {
+                loaded = manifests
+                    .iter()
+                    .filter_map(|m| {
+                        let dir = m.dir.join(directory.unwrap_or(""));
+                        match makefile {
+                            Some(name) => make_targets(&dir, &[name]),
+                            None => make_targets(&dir, MAKEFILES),
+                        }
+                    })
+                    .collect();
+                loaded.iter().collect()
+            } else {
+                manifests.iter().filter_map(|m| m.make.as_ref()).collect()
+            };
+            if known.is_empty() {
+                return Some(match (makefile, directory) {
+                    (Some(name), _) => format!("no {} found", name),
+                    (None, Some(dir)) => format!("no Makefile found in {}", dir),
+                    (None, None) => "no Makefile found".to_string(),
+                });
+            }
+            targets
+                .into_iter()
+                .find(|target| !known.iter().any(|k| k.contains(*target)))
+                .map(|target| format!("no `{}` target in Makefile", target))
+        }
+        "just" => {
+            let recipe = args.iter().find(|arg| !arg.starts_with('-'))?;
+            let known: Vec<_> = manifests.iter().filter_map(|m| m.just.as_ref()).collect();
+            if known.is_empty() {
+                return Some("no justfile found".to_string());
+            }
+            (!known.iter().any(|k| k.contains(*recipe)))
+                .then(|| format!("no `{}` recipe in justfile", recipe))
+        }
+        "npm" | "yarn" | "pnpm" | "bun" => {
+            if args.first() != Some(&"run") {
+                return None;
+            }
+            let script = args.get(1).filter(|arg| !arg.starts_with('-'))?;
+            let known: Vec<_> = manifests.iter().filter_map(|m| m.npm.as_ref()).collect();
+            if known.is_empty() {
+                return Some("no package.json found".to_string());
+            }
+            (!known.iter().any(|k| k.contains(*script)))
+                .then(|| format!("no `{}` script in package.json", script))
+        }
+        "cargo" => {
+            let known: Vec<_> = manifests.iter().filter_map(|m| m.cargo.as_ref()).collect();
+            if known.is_empty() {
+                return None;
+            }
+            args.windows(2).find_map(|pair| {
+                let (kind, names): (&str, fn(&CargoTargets) -> &BTreeSet<String>) = match pair[0] {
+                    "-p" | "--package" => ("package", |c| &c.packages),
+                    "--bin" => ("binary", |c| &c.bins),
+                    "--example" => ("example", |c| &c.examples),
+                    _ => return None,
+                };
+                let name = pair[1];
+                (!known.iter().any(|c| names(c).contains(name)))
+                    .then(|| format!("no `{}` {} in Cargo.toml", name, kind))
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Targets, recipes, scripts and Cargo targets defined in one directory.
+/// `None` means the manifest does not exist.
+#[derive(Default)]
+struct Manifests {
+    dir: PathBuf,
+    make: Option<BTreeSet<String>>,
+    just: Option<BTreeSet<String>>,
+    npm: Option<BTreeSet<String>>,
+    cargo: Option<CargoTargets>,
+}
+
+#[derive(Default)]
+struct CargoTargets {
+    packages: BTreeSet<String>,
+    bins: BTreeSet<String>,
+    examples: BTreeSet<String>,
+}
+
+impl Manifests {
+    fn load(dir: &Path) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+            make: make_targets(dir, MAKEFILES),
+            just: read_first(dir, &["justfile", "Justfile", ".justfile"]).map(|content| {
+                content
+                    .lines()
+                    .filter_map(|line| JUST_RECIPE_REGEX.captures(line))
+                    .filter(|captures| &captures[2] != "=")
+                    .map(|captures| captures[1].to_string())
+                    .collect()
+            }),
+            npm: read_first(dir, &["package.json"]).map(|content| {
+                serde_json::from_str::<serde_json::Value>(&content)
+                    .ok()
+                    .and_then(|json| json.get("scripts")?.as_object().cloned())
+                    .map(|scripts| scripts.keys().cloned().collect())
+                    .unwrap_or_default()
+            }),
+            cargo: dir.join("Cargo.toml").is_file().then(|| {
+                let mut targets = CargoTargets::default();
+                targets.load(dir);
+                targets
+            }),
+        }
+    }
+}
+
+impl CargoTargets {
+    /// Adds the package in `dir` and, for a workspace, every member.
+    fn load(&mut self, dir: &Path) {
+        let Some(manifest) = fs::read_to_string(dir.join("Cargo.toml"))
+            .ok()
+            .and_then(|content| content.parse::<toml::Table>().ok())
+        else {
+            return;
+        };
+
+        if let Some(name) = manifest
+            .get("package")
+            .and_then(|p| p.get("name"))
+            .and_then(|n| n.as_str())
+        {
+            self.packages.insert(name.to_string());
+            if dir.join("src/main.rs").is_file() {
+                self.bins.insert(name.to_string());
+            }
+        }
+
+        for (section, names) in [("bin", &mut self.bins), ("example", &mut self.examples)] {
+            let declared = manifest.get(section).and_then(|t| t.as_array());
+            for target in declared.into_iter().flatten() {
+                if let Some(name) = target.get("name").and_then(|n| n.as_str()) {
+                    names.insert(name.to_string());
+                }
+            }
+        }
+        self.bins.extend(file_stems(&dir.join("src/bin")));
+        self.examples.extend(file_stems(&dir.join("examples")));
+
+        let members = manifest
+            .get("workspace")
+            .and_then(|w| w.get("members"))
+            .and_then(|m| m.as_array());
+        for member in members.into_iter().flatten().filter_map(|m| m.as_str()) {
+            let pattern = dir.join(member).to_string_lossy().to_string();
+            for member_dir in glob::glob(&pattern).into_iter().flatten().flatten() {
+                if member_dir != dir {
+                    self.load(&member_dir);
+                }
+            }
+        }
+    }
+}
+
+/// Targets defined in the first of `names` that exists in `dir`.
+fn make_targets(dir: &Path, names: &[&str]) -> Option<BTreeSet<String>> {
+    let content = read_first(dir, names)?;
+    Some(
+        content
+            .lines()
+            .filter(|line| !line.starts_with('\t'))
+            .filter_map(|line| MAKE_TARGET_REGEX.captures(line))
+            .filter(|captures| &captures[2] != "=")
+            .flat_map(|captures| {
+                captures[1]
+                    .split_whitespace()
+                    .map(String::from)
+                    .collect::<Vec<_>>()
+            })
+            .collect(),
+    )
+}
+
+fn read_first(dir: &Path, names: &[&str]) -> Option<String> {
+    names
+        .iter()
+        .find_map(|name| fs::read_to_string(dir.join(name)).ok())
+}
+
+fn file_stems(dir: &Path) -> Vec<String> {
+    fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.is_dir() {
+                path.join("main.rs")
+                    .is_file()
+                    .then(|| entry.file_name().to_string_lossy().to_string())
+            } else {
+                (path.extension()? == "rs")
+                    .then(|| path.file_stem().map(|s| s.to_string_lossy().to_string()))?
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::MemoryType;
+
+    fn stale(root: &Path, content: &str) -> Vec<(usize, String, String)> {
+        let files = [MemoryFile::from_content(
+            root.join("CLAUDE.md"),
+            MemoryType::ProjectMemory,
+            content,
+        )];
+        StalenessChecker::new(root)
+            .find(&files)
+            .into_iter()
+            .map(|s| (s.line, s.reference, s.reason))
+            .collect()
+    }
+
+    #[test]
+    fn reports_missing_paths_only() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("docs")).unwrap();
+        fs::write(dir.path().join("docs/guide.md"), "").unwrap();
+
+        let found = stale(
+            dir.path(),
+            "See docs/guide.md, and docs/old.md.\nUse and/or CI/CD.\n\
+             Build into target/release/ from github.com/org/repo.git\n",
+        );
+        assert_eq!(
+            found,
+            vec![(
+                1,
+                "docs/old.md".to_string(),
+                "path does not exist".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn checks_commands_against_manifests() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("Makefile"),
+            "VAR = 1\nbuild test: deps\n\tcc\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("package.json"),
+            r#"{"scripts": {"lint": "eslint ."}}"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("Cargo.toml"),
+            "[package]\nname = \"app\"\n[[example]]\nname = \"demo\"\n",
+        )
+        .unwrap();
+
+        let found = stale(
+            dir.path(),
+            "Run `make build test` and `make VAR`.\n\
+             ```\n$ npm run lint && pnpm run format\ncargo run -p app --example demo\n\
+             cargo run --example missing\njust check\n```\n",
+        );
+        let reasons: Vec<(usize, &str)> = found
+            .iter()
+            .map(|(line, _, reason)| (*line, reason.as_str()))
+            .collect();
+        assert_eq!(
+            reasons,
+            vec![
+                (1, "no `VAR` target in Makefile"),
+                (3, "no `format` script in package.json"),
+                (5, "no `missing` example in Cargo.toml"),
+                (6, "no justfile found"),
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_make_option_values() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("web")).unwrap();
+        fs::write(dir.path().join("Makefile"), "build:\n\tcc\n").unwrap();
+        fs::write(dir.path().join("web/Makefile"), "bundle:\n\tnpm\n").unwrap();
+        fs::write(dir.path().join("ci.mk"), "release:\n\tcc\n").unwrap();
+
+        let found = stale(
+            dir.path(),
+            "```
+make -j 8 build
+make -j build -l 2.5
+make -C web bundle
+             make -f ci.mk release
+make -C web build
+make -C docs html
+             make -f other.mk build
+```
+",
+        );
+        let reasons: Vec<(usize, &str)> = found
+            .iter()
+            .map(|(line, _, reason)| (*line, reason.as_str()))
+            .collect();
+        assert_eq!(
+            reasons,
+            vec![
+                (6, "no `build` target in Makefile"),
+                (7, "no Makefile found in docs"),
+                (8, "no other.mk found"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,271 @@
+use super::Diagnostic;
+use crate::core::MemoryFile;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::path::{Path, PathBuf};
+
+static DIRECTIVE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"<!--\s*ccat-(disable-next-line|disable|enable)\b([^>]*?)\s*-->").unwrap()
+});
+
+const FRONTMATTER_KEY: &str = "ccat-disable";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuppressionScope {
+    /// `<!-- ccat-disable-next-line rule -->`
+    NextLine,
+    /// `<!-- ccat-disable rule -->` up to the matching `<!-- ccat-enable -->`
+    Region { end: Option<usize> },
+    /// `ccat-disable: [rule]` in the frontmatter
+    File,
+}
+
+#[derive(Debug, Clone)]
+pub struct Suppression {
+    /// Line of the directive itself.
+    pub line: usize,
+    /// Rule ids; empty suppresses every rule.
+    pub rules: Vec<String>,
+    pub scope: SuppressionScope,
+    pub used: bool,
+}
+
+impl Suppression {
+    pub fn covers(&self, rule: &str, line: Option<usize>) -> bool {
+        if !self.rules.is_empty() && !self.rules.iter().any(|r| r == rule) {
+            return false;
+        }
+        match (self.scope, line) {
+            (SuppressionScope::File, _) => true,
+            (SuppressionScope::NextLine, Some(line)) => line == self.line + 1,
+            (SuppressionScope::Region { end }, Some(line)) => {
+                line > self.line && end.is_none_or(|end| line < end)
+            }
+            (_, None) => false,
+        }
+    }
+}
+
+/// Suppressions of every scanned file, tracking which ones were used.
+pub struct Suppressions {
+    files: Vec<SuppressedFile>,
+}
+
+struct SuppressedFile {
+    path: PathBuf,
+    canonical: Option<PathBuf>,
+    suppressions: Vec<Suppression>,
+}
+
+impl Suppressions {
+    pub fn from_files(files: &[MemoryFile]) -> Self {
+        Self {
+            files: files
+                .iter()
+                .map(|file| SuppressedFile {
+                    path: file.path.clone(),
+                    canonical: file.path.canonicalize().ok(),
+                    suppressions: parse(&file.content),
+                })
+                .filter(|file| !file.suppressions.is_empty())
+                .collect(),
+        }
+    }
+
+    /// Whether `diagnostic` is suppressed; marks the matching suppressions
+    /// as used. Rules that follow imports report canonical paths, so those
+    /// match the scanned file too.
+    pub fn suppress(&mut self, diagnostic: &Diagnostic) -> bool {
+        let Some(file) = &diagnostic.file else {
+            return false;
+        };
+        let file = Path::new(file);
+        let canonical = file.canonicalize().ok();
+        let Some(SuppressedFile { suppressions, .. }) = self.files.iter_mut().find(|suppressed| {
+            suppressed.path == file || (canonical.is_some() && suppressed.canonical == canonical)
+        }) else {
+            return false;
+        };
+
+        let mut suppressed = false;
+        for suppression in suppressions.iter_mut() {
+            if suppression.covers(&diagnostic.code, diagnostic.line) {
+                suppression.used = true;
+                suppressed = true;
+            }
+        }
+        suppressed
+    }
+
+    pub fn unused(&self) -> impl Iterator<Item = (&Path, &Suppression)> {
+        self.files.iter().flat_map(|file| {
+            file.suppressions
+                .iter()
+                .filter(|s| !s.used)
+                .map(move |s| (file.path.as_path(), s))
+        })
+    }
+}
+
+/// Reads the directives of one file. Directives inside code blocks are
+/// ignored.
+pub fn parse(content: &str) -> Vec<Suppression> {
+    let mut suppressions = frontmatter(content);
+    let mut open: Vec<usize> = Vec::new();
+    let mut in_code_block = false;
+
+    for (idx, line) in content.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+
+        for captures in DIRECTIVE_REGEX.captures_iter(line) {
+            let rules = parse_rules(&captures[2]);
+            match &captures[1] {
+                "disable-next-line" => suppressions.push(Suppression {
+                    line: idx + 1,
+                    rules,
+                    scope: SuppressionScope::NextLine,
+                    used: false,
+                }),
+                "disable" => {
+                    open.push(suppressions.len());
+                    suppressions.push(Suppression {
+                        line: idx + 1,
+                        rules,
+                        scope: SuppressionScope::Region { end: None },
+                        used: false,
+                    });
+                }
+                _ => open.retain(|&i| {
+                    let region = &mut suppressions[i];
+                    let closes = rules.is_empty() || region.rules.iter().any(|r| rules.contains(r));
+                    if closes {
+                        region.scope = SuppressionScope::Region { end: Some(idx + 1) };
+                    }
+                    !closes
+                }),
+            }
+        }
+    }
+
+    suppressions
+}
+
+fn parse_rules(text: &str) -> Vec<String> {
+    text.split([',', ' ', '\t'])
+        .map(str::trim)
+        .filter(|rule| !rule.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// `ccat-disable: rule` or `ccat-disable: [rule, ...]` in YAML frontmatter.
+fn frontmatter(content: &str) -> Vec<Suppression> {
+    let mut lines = content.lines().enumerate();
+    if lines.next().map(|(_, line)| line.trim_end()) != Some("---") {
+        return Vec::new();
+    }
+
+    let mut yaml = String::new();
+    let mut key_line = None;
+    for (idx, line) in lines {
+        if line.trim_end() == "---" {
+            let Ok(value) = serde_yaml::from_str::<serde_yaml::Value>(&yaml) else {
+                return Vec::new();
+            };
+            let rules = match value.get(FRONTMATTER_KEY) {
+                Some(serde_yaml::Value::String(rule)) => parse_rules(rule),
+                Some(serde_yaml::Value::Sequence(rules)) => rules
+                    .iter()
+                    .filter_map(|rule| rule.as_str().map(String::from))
+                    .collect(),
+                _ => return Vec::new(),
+            };
+            return vec![Suppression {
+                line: key_line.unwrap_or(1),
+                rules,
+                scope: SuppressionScope::File,
+                used: false,
+            }];
+        }
+        if line.starts_with(FRONTMATTER_KEY) {
+            key_line = Some(idx + 1);
+        }
+        yaml.push_str(line);
+        yaml.push('\n');
+    }
+
+    Vec::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::Diagnostics;
+    use crate::core::{Config, ImportResolver, Scanner};
+    use std::fs;
+
+    #[test]
+    fn covers_next_line_and_regions() {
+        let content = "<!-- ccat-disable-next-line a -->\nx\ny\n<!-- ccat-disable b -->\nz\n<!-- ccat-enable b -->\nw\n";
+        let suppressions = parse(content);
+        assert_eq!(suppressions.len(), 2);
+
+        let next_line = &suppressions[0];
+        assert!(next_line.covers("a", Some(2)));
+        assert!(!next_line.covers("a", Some(3)));
+        assert!(!next_line.covers("b", Some(2)));
+
+        let region = &suppressions[1];
+        assert_eq!(region.scope, SuppressionScope::Region { end: Some(6) });
+        assert!(region.covers("b", Some(5)));
+        assert!(!region.covers("b", Some(7)));
+        assert!(!region.covers("b", None));
+    }
+
+    #[test]
+    fn parses_frontmatter_and_ignores_code_blocks() {
+        let content = "---\nccat-disable: [a, b]\n---\n```\n<!-- ccat-disable c -->\n```\n";
+        let suppressions = parse(content);
+        assert_eq!(suppressions.len(), 1);
+        assert_eq!(suppressions[0].scope, SuppressionScope::File);
+        assert_eq!(suppressions[0].line, 2);
+        assert!(suppressions[0].covers("b", None));
+        assert!(!suppressions[0].covers("c", Some(1)));
+    }
+
+    #[test]
+    fn suppresses_circular_import_reported_at_canonical_path() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("sub")).unwrap();
+        fs::write(
+            dir.path().join("CLAUDE.md"),
+            "# Project\n\n<!-- ccat-disable-next-line circular-import -->\n@notes.md\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("notes.md"), "@CLAUDE.md\n").unwrap();
+
+        // Scanned paths are not canonical, the import graph's are
+        let root = dir.path().join("sub/..");
+        let config = Config::default();
+        let mut files = Scanner::new().scan(&root).unwrap();
+        let mut resolver = ImportResolver::from_config(&root, &config);
+        resolver.resolve_all(&mut files).unwrap();
+        let graph = resolver.build_graph(&files).unwrap();
+        assert_eq!(graph.cycles().len(), 1);
+
+        let result = Diagnostics::new()
+            .with_project_root(&root)
+            .check_with_graph(&files, &graph)
+            .unwrap();
+        let codes: Vec<&str> = result.diagnostics().map(|d| d.code.as_str()).collect();
+        assert!(!codes.contains(&"circular-import"), "{:?}", codes);
+        assert!(!codes.contains(&"unused-suppression"), "{:?}", codes);
+    }
+}
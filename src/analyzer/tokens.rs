@@ -0,0 +1,8 @@
+/// Characters per token assumed by `estimate_tokens`.
+pub const CHARS_PER_TOKEN: usize = 4;
+
+/// Rough token count of `text` for Claude models: about four characters per
+/// token for English prose and code. Good enough for budgets, not billing.
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(CHARS_PER_TOKEN)
+}
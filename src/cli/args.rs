@@ -1,3 +1,4 @@
+use crate::analyzer::DiagnosticLevel;
 use clap::Args;
 use std::path::PathBuf;
 
@@ -41,17 +42,93 @@ pub struct DiagnoseArgs {
     #[arg(value_name = "PATH", default_value = ".")]
     pub path: PathBuf,
 
+    #[arg(short, long, value_enum, default_value = "text")]
+    pub format: DiagnoseFormat,
+
+    #[arg(short = 's', long)]
+    pub include_subdirs: bool,
+
     #[arg(long)]
     pub fix: bool,
 
+    #[arg(long, requires = "fix")]
+    pub dry_run: bool,
+
     #[arg(long)]
     pub strict: bool,
 
     #[arg(long)]
-    pub rules: Vec<String>,
+    pub all_rules: bool,
 
     #[arg(long)]
+    pub warnings_as_errors: bool,
+
+    #[arg(long, value_name = "FILE")]
+    pub rules: Vec<PathBuf>,
+
+    #[arg(long, value_name = "RULE", value_delimiter = ',')]
     pub ignore: Vec<String>,
+
+    #[arg(long, value_name = "RULE", value_delimiter = ',')]
+    pub enable: Vec<String>,
+
+    #[arg(long, value_name = "RULE=LEVEL")]
+    pub severity: Vec<String>,
+
+    #[arg(long, value_name = "FILE")]
+    pub baseline: Option<PathBuf>,
+
+    #[arg(long, value_name = "FILE")]
+    pub write_baseline: Option<Option<PathBuf>>,
+
+    #[arg(long, value_enum, default_value = "error")]
+    pub fail_on: FailOn,
+
+    #[arg(long, value_name = "N")]
+    pub max_warnings: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum FailOn {
+    Error,
+    Warning,
+    Info,
+}
+
+impl From<FailOn> for DiagnosticLevel {
+    fn from(fail_on: FailOn) -> Self {
+        match fail_on {
+            FailOn::Error => DiagnosticLevel::Error,
+            FailOn::Warning => DiagnosticLevel::Warning,
+            FailOn::Info => DiagnosticLevel::Info,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum DiagnoseFormat {
+    Text,
+    Json,
+    Sarif,
+    Junit,
+    Checkstyle,
+    Github,
+    Markdown,
+}
+
+#[derive(Debug, Args)]
+pub struct RulesArgs {
+    #[command(subcommand)]
+    pub command: RulesCommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum RulesCommand {
+    #[command(about = "List available rules")]
+    List,
+
+    #[command(about = "Explain what a rule checks")]
+    Explain { id: String },
 }
 
 #[derive(Debug, Args)]
@@ -139,6 +216,18 @@ pub enum ExportFormat {
     Pdf,
 }
 
+#[derive(Debug, Args)]
+pub struct LocateArgs {
+    #[arg(value_name = "LINE")]
+    pub line: usize,
+
+    #[arg(value_name = "PATH", default_value = ".")]
+    pub path: PathBuf,
+
+    #[arg(long)]
+    pub no_expand: bool,
+}
+
 #[derive(Debug, Args)]
 pub struct ValidateArgs {
     #[arg(value_name = "PATH", default_value = ".")]
@@ -147,8 +236,48 @@ pub struct ValidateArgs {
     #[arg(long)]
     pub strict: bool,
 
+    #[arg(long)]
+    pub all_rules: bool,
+
+    #[arg(long)]
+    pub warnings_as_errors: bool,
+
     #[arg(long)]
     pub fix: bool,
+
+    #[arg(long, requires = "fix")]
+    pub dry_run: bool,
+
+    #[arg(long, value_enum, default_value = "error")]
+    pub fail_on: FailOn,
+
+    #[arg(long, value_name = "N")]
+    pub max_warnings: Option<usize>,
+}
+
+#[derive(Debug, Args)]
+pub struct GraphArgs {
+    #[arg(value_name = "PATH", default_value = ".")]
+    pub path: PathBuf,
+
+    #[arg(short, long, value_enum, default_value = "tree")]
+    pub format: GraphFormat,
+
+    #[arg(short = 'o', long)]
+    pub output: Option<PathBuf>,
+
+    #[arg(short = 's', long)]
+    pub include_subdirs: bool,
+
+    #[arg(short = 'd', long)]
+    pub max_depth: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum GraphFormat {
+    Dot,
+    Mermaid,
+    Tree,
 }
 
 #[derive(Debug, Args)]
@@ -165,3 +294,21 @@ pub struct InitArgs {
     #[arg(long)]
     pub force: bool,
 }
+
+#[derive(Debug, Args)]
+pub struct MigrateArgs {
+    #[arg(value_name = "PATH", default_value = ".")]
+    pub path: PathBuf,
+
+    #[arg(long, value_name = "IMPORT", conflicts_with = "keep")]
+    pub to: Option<String>,
+
+    #[arg(long)]
+    pub keep: bool,
+
+    #[arg(long, conflicts_with = "keep")]
+    pub no_import: bool,
+
+    #[arg(long)]
+    pub dry_run: bool,
+}
@@ -1,8 +1,286 @@
-use crate::cli::args::DiagnoseArgs;
-use anyhow::Result;
+use crate::analyzer::baseline::DEFAULT_BASELINE_FILE;
+use crate::analyzer::{
+    Baseline, DiagnosticLevel, DiagnosticResult, Diagnostics, FixEngine, RuleRegistry,
+};
+use crate::cli::args::{DiagnoseArgs, DiagnoseFormat};
+use crate::cli::CheckFailed;
+use crate::core::parser::ParsedContent;
+use crate::core::{Config, ImportGraph, ImportResolver, MemoryFile, Scanner, ShardedCache};
+use crate::formatter::{
+    plural, CheckstyleReport, GithubReport, JsonReport, JunitReport, MarkdownReport,
+    ReportFormatter, SarifReport, TextReport,
+};
+use anyhow::{bail, Context, Result};
+use std::path::Path;
+use std::sync::Arc;
+
+pub fn execute(args: DiagnoseArgs) -> Result<()> {
+    let config = Config::load(&args.path)?;
+
+    let caches = Caches::new(&config)?;
+
+    let severities = parse_severities(&args.severity)?;
+    let registry = RuleRegistry::with_custom_rules(&config, &args.path, &args.rules)?;
+    let mut diagnostics = Diagnostics::new()
+        .with_registry(registry)
+        .with_config(config.clone())
+        .with_parse_cache(Arc::clone(&caches.parsed))
+        .with_project_root(&args.path)
+        .with_all_rules(args.strict || args.all_rules)
+        .with_warnings_as_errors(args.strict || args.warnings_as_errors)
+        .with_enabled_rules(args.enable.iter().cloned())
+        .with_disabled_rules(args.ignore.iter().cloned());
+    for (id, level) in &severities {
+        diagnostics = diagnostics.with_severity(id.clone(), *level);
+    }
+
+    let requested = args
+        .enable
+        .iter()
+        .chain(&args.ignore)
+        .chain(severities.iter().map(|(id, _)| id));
+    for id in requested {
+        if !diagnostics.registry().contains(id) {
+            bail!("Unknown rule: {} (see `ccat rules list`)", id);
+        }
+    }
+
+    let (mut files, graph) = load(&args.path, args.include_subdirs, &config, &caches.files)?;
+    let mut result = diagnostics.check_with_graph(&files, &graph)?;
+
+    if args.fix {
+        apply_fixes(&result, args.dry_run)?;
+        if args.dry_run {
+            return Ok(());
+        }
+        let (fixed_files, graph) = load(&args.path, args.include_subdirs, &config, &caches.files)?;
+        result = diagnostics.check_with_graph(&fixed_files, &graph)?;
+        files = fixed_files;
+    }
+
+    if let Some(path) = &args.write_baseline {
+        let path = path
+            .clone()
+            .unwrap_or_else(|| args.path.join(DEFAULT_BASELINE_FILE));
+        let baseline = Baseline::from_result(&result, &files, &args.path);
+        baseline.save(&path)?;
+        eprintln!(
+            "Wrote {} entries to baseline {}",
+            baseline.entries.len(),
+            path.display()
+        );
+        return Ok(());
+    }
+
+    if let Some(path) = &args.baseline {
+        Baseline::load(path)?.apply(&mut result, &files, &args.path);
+    }
+
+    let output = match args.format {
+        DiagnoseFormat::Text => TextReport::new().render(&result)?,
+        DiagnoseFormat::Json => JsonReport::new().render(&result)?,
+        DiagnoseFormat::Sarif => SarifReport::new(diagnostics.registry())
+            .with_project_root(&args.path)
+            .render(&result)?,
+        DiagnoseFormat::Junit => JunitReport::new().render(&result)?,
+        DiagnoseFormat::Checkstyle => CheckstyleReport::new().render(&result)?,
+        DiagnoseFormat::Github => GithubReport::new().render(&result)?,
+        DiagnoseFormat::Markdown => MarkdownReport::new().render(&result)?,
+    };
+    print!("{}", output);
+
+    check_thresholds(&result, args.fail_on.into(), args.max_warnings)
+}
+
+/// Fails with `CheckFailed` when `result` has findings at or above
+/// `fail_on`, or more warnings than `max_warnings`.
+pub(crate) fn check_thresholds(
+    result: &DiagnosticResult,
+    fail_on: DiagnosticLevel,
+    max_warnings: Option<usize>,
+) -> Result<()> {
+    let failing = result.count_at_least(fail_on);
+    if failing > 0 {
+        return Err(CheckFailed(format!(
+            "Found {} {} at or above {} level",
+            failing,
+            plural(failing, "diagnostic", "diagnostics"),
+            fail_on
+        ))
+        .into());
+    }
+
+    if let Some(max) = max_warnings.filter(|max| result.warnings.len() > *max) {
+        return Err(CheckFailed(format!(
+            "Found {} {} (maximum is {})",
+            result.warnings.len(),
+            plural(result.warnings.len(), "warning", "warnings"),
+            max
+        ))
+        .into());
+    }
 
-pub fn execute(_args: DiagnoseArgs) -> Result<()> {
-    // TODO: Implement diagnose command
-    println!("Diagnose command not yet implemented");
     Ok(())
 }
+
+/// Caches for scanned files and parse results, sharing the configured
+/// `performance.cache_size` and kept for the whole run, so files unchanged
+/// by `--fix` are not read and parsed again.
+pub(crate) struct Caches {
+    pub files: Arc<ShardedCache<MemoryFile>>,
+    pub parsed: Arc<ShardedCache<ParsedContent>>,
+}
+
+impl Caches {
+    pub(crate) fn new(config: &Config) -> Result<Self> {
+        let cache_bytes = config.performance.cache_bytes()? / 2;
+        Ok(Self {
+            files: Arc::new(ShardedCache::new(cache_bytes)),
+            parsed: Arc::new(ShardedCache::new(cache_bytes)),
+        })
+    }
+}
+
+/// Scans and resolves memory files the same way for every diagnostics run.
+pub(crate) fn load(
+    path: &Path,
+    include_subdirs: bool,
+    config: &Config,
+    cache: &Arc<ShardedCache<MemoryFile>>,
+) -> Result<(Vec<MemoryFile>, ImportGraph)> {
+    let mut files = Scanner::new()
+        .with_subdirs(include_subdirs)
+        .with_parallel(config.performance.parallel)
+        .with_cache(Arc::clone(cache))
+        .scan(path)
+        .context("Failed to scan for CLAUDE.md files")?;
+
+    let mut resolver = ImportResolver::from_config(path, config);
+    resolver.resolve_all(&mut files)?;
+    let graph = resolver.build_graph(&files)?;
+
+    Ok((files, graph))
+}
+
+/// Applies the fixes attached to `result`, or prints them as a unified diff
+/// when `dry_run` is set.
+pub(crate) fn apply_fixes(result: &DiagnosticResult, dry_run: bool) -> Result<()> {
+    let mut engine = FixEngine::new();
+    for fix in result.diagnostics().filter_map(|d| d.fix.clone()) {
+        engine.add(fix);
+    }
+    let plan = engine.plan()?;
+    let file_count = plan.files().count();
+
+    if !plan.skipped.is_empty() {
+        eprintln!(
+            "{} overlapping {} skipped; run again to apply {}",
+            plan.skipped.len(),
+            plural(plan.skipped.len(), "fix", "fixes"),
+            plural(plan.skipped.len(), "it", "them")
+        );
+    }
+
+    if dry_run {
+        print!("{}", plan.diff());
+        eprintln!(
+            "{} {} would change {} {}",
+            plan.applied.len(),
+            plural(plan.applied.len(), "fix", "fixes"),
+            file_count,
+            plural(file_count, "file", "files")
+        );
+        return Ok(());
+    }
+
+    plan.apply()?;
+    eprintln!(
+        "Applied {} {} to {} {}",
+        plan.applied.len(),
+        plural(plan.applied.len(), "fix", "fixes"),
+        file_count,
+        plural(file_count, "file", "files")
+    );
+    Ok(())
+}
+
+fn parse_severities(values: &[String]) -> Result<Vec<(String, DiagnosticLevel)>> {
+    values
+        .iter()
+        .map(|value| {
+            let Some((id, level)) = value.split_once('=') else {
+                bail!("Invalid --severity {} (expected RULE=LEVEL)", value);
+            };
+            Ok((id.trim().to_string(), level.trim().parse()?))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::{Diagnostic, PerformanceMetrics};
+
+    fn result(levels: &[DiagnosticLevel]) -> DiagnosticResult {
+        let mut result = DiagnosticResult {
+            errors: Vec::new(),
+            warnings: Vec::new(),
+            infos: Vec::new(),
+            suggestions: Vec::new(),
+            metrics: PerformanceMetrics::default(),
+            baseline: None,
+        };
+        for level in levels {
+            result.push(Diagnostic::new(*level, "rule", "found"));
+        }
+        result
+    }
+
+    /// The `CheckFailed` message, or `None` if the thresholds pass.
+    fn failure(
+        result: &DiagnosticResult,
+        fail_on: DiagnosticLevel,
+        max_warnings: Option<usize>,
+    ) -> Option<String> {
+        check_thresholds(result, fail_on, max_warnings)
+            .err()
+            .map(|err| err.downcast::<CheckFailed>().unwrap().0)
+    }
+
+    #[test]
+    fn max_warnings_zero_fails_on_one_warning() {
+        let one = result(&[DiagnosticLevel::Warning]);
+        assert_eq!(
+            failure(&one, DiagnosticLevel::Error, Some(0)).as_deref(),
+            Some("Found 1 warning (maximum is 0)")
+        );
+        assert_eq!(failure(&one, DiagnosticLevel::Error, Some(1)), None);
+        assert_eq!(failure(&one, DiagnosticLevel::Error, None), None);
+
+        let two = result(&[DiagnosticLevel::Warning, DiagnosticLevel::Warning]);
+        assert_eq!(
+            failure(&two, DiagnosticLevel::Error, Some(1)).as_deref(),
+            Some("Found 2 warnings (maximum is 1)")
+        );
+    }
+
+    #[test]
+    fn fail_on_counts_findings_at_or_above_the_level() {
+        let warnings = result(&[DiagnosticLevel::Warning, DiagnosticLevel::Info]);
+        assert_eq!(failure(&warnings, DiagnosticLevel::Error, None), None);
+        assert_eq!(
+            failure(&warnings, DiagnosticLevel::Warning, None).as_deref(),
+            Some("Found 1 diagnostic at or above warning level")
+        );
+        assert_eq!(
+            failure(&warnings, DiagnosticLevel::Info, None).as_deref(),
+            Some("Found 2 diagnostics at or above info level")
+        );
+
+        let errors = result(&[DiagnosticLevel::Error, DiagnosticLevel::Error]);
+        assert_eq!(
+            failure(&errors, DiagnosticLevel::Error, Some(5)).as_deref(),
+            Some("Found 2 diagnostics at or above error level")
+        );
+    }
+}
@@ -1,8 +1,59 @@
-use crate::cli::args::ExportArgs;
-use anyhow::Result;
+use crate::cli::args::{ExportArgs, ExportFormat};
+use crate::core::{Config, ImportResolver, MemoryFile, Scanner, SourceMap, MAX_IMPORT_DEPTH};
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use serde::Serialize;
+use std::fs;
+
+#[derive(Serialize)]
+struct ExportDocument<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    files: Option<&'a [MemoryFile]>,
+    content: &'a str,
+    source_map: &'a SourceMap,
+}
+
+pub fn execute(args: ExportArgs) -> Result<()> {
+    let mut files = Scanner::new()
+        .scan(&args.path)
+        .context("Failed to scan for CLAUDE.md files")?;
+
+    if files.is_empty() {
+        eprintln!("{}", "No CLAUDE.md files found".yellow());
+        return Ok(());
+    }
+
+    let config = Config::load(&args.path)?;
+    let mut resolver = ImportResolver::from_config(&args.path, &config);
+    resolver.resolve_all(&mut files)?;
+
+    let max_depth = if args.expand_imports {
+        MAX_IMPORT_DEPTH
+    } else {
+        0
+    };
+    let context = resolver.expand_all(&files, max_depth)?;
+
+    let output = match args.format {
+        ExportFormat::Markdown => context.content.clone(),
+        ExportFormat::Json => {
+            let document = ExportDocument {
+                files: args.include_metadata.then_some(files.as_slice()),
+                content: &context.content,
+                source_map: &context.source_map,
+            };
+            format!("{}\n", serde_json::to_string_pretty(&document)?)
+        }
+        ExportFormat::Html | ExportFormat::Pdf => {
+            bail!("{:?} export is not supported yet", args.format)
+        }
+    };
+
+    match args.output {
+        Some(path) => fs::write(&path, output)
+            .with_context(|| format!("Failed to write {}", path.display()))?,
+        None => print!("{}", output),
+    }
 
-pub fn execute(_args: ExportArgs) -> Result<()> {
-    // TODO: Implement export command
-    println!("Export command not yet implemented");
     Ok(())
 }
@@ -0,0 +1,41 @@
+use crate::cli::args::{GraphArgs, GraphFormat};
+use crate::core::{Config, ImportResolver, Scanner};
+use crate::formatter::GraphFormatter;
+use anyhow::{Context, Result};
+use colored::Colorize;
+use std::fs;
+
+pub fn execute(args: GraphArgs) -> Result<()> {
+    let scanner = Scanner::new()
+        .with_subdirs(args.include_subdirs)
+        .with_max_depth(args.max_depth);
+
+    let mut files = scanner
+        .scan(&args.path)
+        .context("Failed to scan for CLAUDE.md files")?;
+
+    if files.is_empty() {
+        eprintln!("{}", "No CLAUDE.md files found".yellow());
+        return Ok(());
+    }
+
+    let config = Config::load(&args.path)?;
+    let mut resolver = ImportResolver::from_config(&args.path, &config);
+    resolver.resolve_all(&mut files)?;
+    let graph = resolver.build_graph(&files)?;
+
+    let formatter = GraphFormatter::new().with_base_path(&args.path);
+    let rendered = match args.format {
+        GraphFormat::Dot => formatter.to_dot(&graph),
+        GraphFormat::Mermaid => formatter.to_mermaid(&graph),
+        GraphFormat::Tree => formatter.to_tree(&graph),
+    };
+
+    match args.output {
+        Some(output) => fs::write(&output, rendered)
+            .with_context(|| format!("Failed to write {}", output.display()))?,
+        None => print!("{}", rendered),
+    }
+
+    Ok(())
+}
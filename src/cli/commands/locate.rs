@@ -0,0 +1,45 @@
+use crate::cli::args::LocateArgs;
+use crate::core::{Config, ImportResolver, Scanner, MAX_IMPORT_DEPTH};
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use std::fs;
+
+pub fn execute(args: LocateArgs) -> Result<()> {
+    let mut files = Scanner::new()
+        .scan(&args.path)
+        .context("Failed to scan for CLAUDE.md files")?;
+
+    let config = Config::load(&args.path)?;
+    let mut resolver = ImportResolver::from_config(&args.path, &config);
+    resolver.resolve_all(&mut files)?;
+
+    // Rebuild the document exactly as `export` does so line numbers agree
+    let max_depth = if args.no_expand { 0 } else { MAX_IMPORT_DEPTH };
+    let context = resolver.expand_all(&files, max_depth)?;
+
+    let Some(location) = context.locate(args.line) else {
+        bail!(
+            "Line {} is out of range (expanded output has {} lines)",
+            args.line,
+            context.line_count()
+        );
+    };
+
+    if location.generated {
+        println!(
+            "{} {}",
+            location.to_string().bold(),
+            "(generated by ccat)".dimmed()
+        );
+    } else {
+        println!("{}", location.to_string().bold());
+    }
+
+    if let Ok(content) = fs::read_to_string(&location.file) {
+        if let Some(line) = content.lines().nth(location.line.saturating_sub(1)) {
+            println!("{:4} │ {}", location.line.to_string().dimmed(), line);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,333 @@
+use super::diagnose::{load, Caches};
+use crate::analyzer::rules::files::{home_import, local_import_target, move_local_memory};
+use crate::analyzer::{Fix, FixEngine, TextEdit};
+use crate::cli::args::MigrateArgs;
+use crate::core::{Config, MemoryFile, MemoryType};
+use anyhow::{bail, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+const GITIGNORE_ENTRY: &str = "CLAUDE.local.md";
+
+pub fn execute(args: MigrateArgs) -> Result<()> {
+    let config = Config::load(&args.path)?;
+    // Subdirectory memory files may import CLAUDE.local.md too
+    let (files, _) = load(&args.path, true, &config, &Caches::new(&config)?.files)?;
+
+    let Some(local) = files
+        .iter()
+        .find(|file| file.file_type == MemoryType::LocalMemory)
+    else {
+        eprintln!(
+            "No CLAUDE.local.md in {}; nothing to migrate",
+            args.path.display()
+        );
+        return Ok(());
+    };
+
+    if args.keep {
+        keep_ignored(local, args.dry_run)
+    } else {
+        let import = args.to.clone().unwrap_or_else(|| home_import(&args.path));
+        move_to_import(local, &files, &import, !args.no_import, args.dry_run)
+    }
+}
+
+fn move_to_import(
+    local: &MemoryFile,
+    files: &[MemoryFile],
+    import: &str,
+    edit_imports: bool,
+    dry_run: bool,
+) -> Result<()> {
+    let target = local_import_target(local, import);
+    let Some(fix) = move_local_memory(local, files, import, edit_imports) else {
+        bail!(
+            "{} already exists; choose another file with --to",
+            target.display()
+        );
+    };
+
+    let dir = local.path.parent().unwrap_or(Path::new("."));
+    let tracked = matches!(
+        git_status(dir, &local.path),
+        Git::Inside { tracked: true, .. }
+    );
+    apply(fix, dry_run)?;
+
+    if tracked {
+        eprintln!(
+            "{} is tracked by git; commit its removal",
+            local.path.display()
+        );
+    }
+    if !edit_imports {
+        eprintln!(
+            "CLAUDE.md was left unchanged; import {} from ~/.claude/CLAUDE.md to keep loading it",
+            import
+        );
+    } else if !absolute(&target).starts_with(absolute(dir)) {
+        // CLAUDE.md is shared, but the imported file only exists on this machine
+        eprintln!(
+            "Warning: CLAUDE.md imports {}, which teammates do not have; \
+             use --no-import to keep the import out of CLAUDE.md",
+            import
+        );
+    }
+    // Personal instructions must not end up in the repository again
+    match git_status(dir, &target) {
+        Git::Inside { ignored: false, .. } => eprintln!(
+            "Warning: {} is inside the repository but not git-ignored",
+            target.display()
+        ),
+        Git::Inside { ignored: true, .. } => {
+            eprintln!("Verified: {} is git-ignored", target.display())
+        }
+        Git::Outside | Git::NoRepository => {
+            eprintln!("Verified: {} is outside the repository", target.display())
+        }
+    }
+    Ok(())
+}
+
+/// Keeps CLAUDE.local.md where it is and adds it to the `.gitignore` next to
+/// it unless git already ignores it.
+fn keep_ignored(local: &MemoryFile, dry_run: bool) -> Result<()> {
+    let dir = local.path.parent().unwrap_or(Path::new("."));
+    let (tracked, ignored) = match git_status(dir, &local.path) {
+        Git::Inside { tracked, ignored } => (tracked, ignored),
+        Git::Outside | Git::NoRepository => bail!(
+            "{} is not inside a git repository; cannot check whether it is ignored",
+            local.path.display()
+        ),
+    };
+
+    if ignored {
+        eprintln!("{} is already git-ignored", local.path.display());
+    } else {
+        let gitignore = dir.join(".gitignore");
+        let content = fs::read_to_string(&gitignore).unwrap_or_default();
+        let separator = if content.is_empty() || content.ends_with('\n') {
+            ""
+        } else {
+            "\n"
+        };
+        let fix =
+            Fix::new(format!("Add {} to .gitignore", GITIGNORE_ENTRY)).with_edit(TextEdit::insert(
+                &gitignore,
+                content.len(),
+                format!("{}{}\n", separator, GITIGNORE_ENTRY),
+            ));
+        apply(fix, dry_run)?;
+
+        if !dry_run
+            && !matches!(
+                git_status(dir, &local.path),
+                Git::Inside { ignored: true, .. }
+            )
+        {
+            bail!(
+                "{} is still not ignored; check the .gitignore negations",
+                local.path.display()
+            );
+        }
+        if !dry_run {
+            eprintln!("Verified: {} is git-ignored", local.path.display());
+        }
+    }
+
+    if tracked {
+        eprintln!(
+            "{} is tracked by git; untrack it with `git rm --cached {}`",
+            local.path.display(),
+            local.path.display()
+        );
+    }
+    Ok(())
+}
+
+/// Applies `fix`, or prints it as a unified diff when `dry_run` is set, and
+/// lists the files it touches.
+fn apply(fix: Fix, dry_run: bool) -> Result<()> {
+    let mut engine = FixEngine::new();
+    engine.add(fix.clone());
+    let plan = engine.plan()?;
+    if !plan.skipped.is_empty() {
+        bail!("Files changed while migrating; run again");
+    }
+
+    let changes: Vec<(&str, &Path)> = plan
+        .files()
+        .map(|path| {
+            let change = if fix.delete.iter().any(|deleted| deleted == path) {
+                "delete"
+            } else if path.exists() {
+                "update"
+            } else {
+                "create"
+            };
+            (change, path)
+        })
+        .collect();
+
+    if dry_run {
+        print!("{}", plan.diff());
+        eprintln!("Would {}", lowercase_first(&fix.description));
+    } else {
+        plan.apply()?;
+        eprintln!("{}", fix.description);
+    }
+    for (change, path) in changes {
+        eprintln!("  {:<6} {}", change, path.display());
+    }
+    Ok(())
+}
+
+fn lowercase_first(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+enum Git {
+    NoRepository,
+    /// The path is outside the work tree of the repository.
+    Outside,
+    Inside {
+        tracked: bool,
+        /// Matched by an ignore pattern, whether or not it is tracked.
+        ignored: bool,
+    },
+}
+
+/// How the repository containing `dir` treats `path`, which need not exist.
+fn git_status(dir: &Path, path: &Path) -> Git {
+    let Some(toplevel) = git(dir, &["rev-parse", "--show-toplevel"]) else {
+        return Git::NoRepository;
+    };
+    let toplevel = PathBuf::from(String::from_utf8_lossy(&toplevel.stdout).trim());
+    let toplevel = toplevel.canonicalize().unwrap_or(toplevel);
+
+    let path = absolute(path);
+    if !path.starts_with(&toplevel) {
+        return Git::Outside;
+    }
+    let path = path.to_string_lossy();
+
+    Git::Inside {
+        tracked: git(dir, &["ls-files", "--error-unmatch", "--", &path]).is_some(),
+        ignored: git(dir, &["check-ignore", "-q", "--no-index", "--", &path]).is_some(),
+    }
+}
+
+/// Runs git in `dir`; `None` if it could not be run or failed.
+fn git(dir: &Path, args: &[&str]) -> Option<Output> {
+    Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+}
+
+/// Canonical form of `path`, resolving only its existing ancestors.
+fn absolute(path: &Path) -> PathBuf {
+    if let Ok(canonical) = path.canonicalize() {
+        return canonical;
+    }
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) if !parent.as_os_str().is_empty() => absolute(parent).join(name),
+        _ => std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Project {
+        dir: tempfile::TempDir,
+        // Stands in for ~/.claude, outside the repository
+        home: tempfile::TempDir,
+    }
+
+    impl Project {
+        fn new(memory: &str) -> Self {
+            let project = Self {
+                dir: tempfile::tempdir().unwrap(),
+                home: tempfile::tempdir().unwrap(),
+            };
+            fs::write(project.dir.path().join("CLAUDE.md"), memory).unwrap();
+            fs::write(project.dir.path().join("CLAUDE.local.md"), "Personal\n").unwrap();
+            project
+        }
+
+        fn target(&self) -> PathBuf {
+            self.home.path().join("personal.md")
+        }
+
+        fn migrate(&self, no_import: bool) {
+            execute(MigrateArgs {
+                path: self.dir.path().to_path_buf(),
+                to: Some(self.target().to_string_lossy().to_string()),
+                keep: false,
+                no_import,
+                dry_run: false,
+            })
+            .unwrap();
+        }
+
+        fn read(&self, name: &str) -> String {
+            fs::read_to_string(self.dir.path().join(name)).unwrap()
+        }
+    }
+
+    #[test]
+    fn moves_local_memory_into_an_import() {
+        let project = Project::new("# Project\nRun tests.\n");
+        project.migrate(false);
+
+        assert!(!project.dir.path().join("CLAUDE.local.md").exists());
+        assert_eq!(fs::read_to_string(project.target()).unwrap(), "Personal\n");
+        assert_eq!(
+            project.read("CLAUDE.md"),
+            format!("# Project\nRun tests.\n\n@{}\n", project.target().display())
+        );
+    }
+
+    #[test]
+    fn rewrites_existing_imports_of_local_memory() {
+        let project = Project::new("# Project\n@CLAUDE.local.md\nRun tests.\n");
+        project.migrate(false);
+
+        assert_eq!(
+            project.read("CLAUDE.md"),
+            format!("# Project\n@{}\nRun tests.\n", project.target().display())
+        );
+    }
+
+    #[test]
+    fn running_twice_changes_nothing() {
+        let project = Project::new("# Project\n");
+        project.migrate(false);
+        let memory = project.read("CLAUDE.md");
+
+        project.migrate(false);
+        assert_eq!(project.read("CLAUDE.md"), memory);
+        assert_eq!(fs::read_to_string(project.target()).unwrap(), "Personal\n");
+    }
+
+    #[test]
+    fn no_import_only_moves_the_file() {
+        let project = Project::new("# Project\n@CLAUDE.local.md\n");
+        project.migrate(true);
+
+        assert!(!project.dir.path().join("CLAUDE.local.md").exists());
+        assert_eq!(fs::read_to_string(project.target()).unwrap(), "Personal\n");
+        assert_eq!(project.read("CLAUDE.md"), "# Project\n@CLAUDE.local.md\n");
+    }
+}
@@ -1,7 +1,11 @@
 pub mod diagnose;
 pub mod diff;
 pub mod export;
+pub mod graph;
 pub mod init;
+pub mod locate;
+pub mod migrate;
+pub mod rules;
 pub mod search;
 pub mod show;
 pub mod validate;
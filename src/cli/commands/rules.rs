@@ -0,0 +1,48 @@
+use crate::analyzer::RuleRegistry;
+use crate::cli::args::{RulesArgs, RulesCommand};
+use crate::core::Config;
+use anyhow::{bail, Result};
+use colored::Colorize;
+use std::path::Path;
+
+pub fn execute(args: RulesArgs) -> Result<()> {
+    let config = Config::load(".")?;
+    let registry = RuleRegistry::with_custom_rules(&config, Path::new("."), &[])?;
+
+    match args.command {
+        RulesCommand::List => {
+            let width = registry.rules().map(|r| r.id().len()).max().unwrap_or(0);
+            for rule in registry.rules() {
+                let status = if rule.enabled_by_default() {
+                    String::new()
+                } else {
+                    format!(" {}", "(opt-in)".dimmed())
+                };
+                println!(
+                    "{:width$}  {:8} {}{}",
+                    rule.id().bold(),
+                    rule.default_level().to_string(),
+                    rule.description(),
+                    status,
+                    width = width
+                );
+            }
+        }
+        RulesCommand::Explain { id } => {
+            let Some(rule) = registry.get(&id) else {
+                bail!("Unknown rule: {} (see `ccat rules list`)", id);
+            };
+            println!("{} ({})", rule.id().bold(), rule.default_level());
+            println!("{}\n", rule.description());
+            println!("{}", rule.explanation());
+            if !rule.enabled_by_default() {
+                println!(
+                    "\n{}",
+                    "Opt-in: enable with --enable or --all-rules.".dimmed()
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
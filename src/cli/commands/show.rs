@@ -1,13 +1,20 @@
 use crate::cli::args::{ShowArgs, ShowFormat};
-use crate::core::{MemoryType, Parser, Scanner};
+use crate::core::{Config, ImportResolver, MemoryType, Scanner, ShardedCache};
 use crate::formatter::{Formatter, JsonFormatter, TextFormatter, TreeFormatter};
 use anyhow::{Context, Result};
 use colored::Colorize;
+use std::sync::Arc;
 
 pub fn execute(args: ShowArgs) -> Result<()> {
+    let config = Config::load(&args.path)?;
+
     let scanner = Scanner::new()
         .with_subdirs(args.include_subdirs)
-        .with_max_depth(args.max_depth);
+        .with_max_depth(args.max_depth)
+        .with_parallel(config.performance.parallel)
+        .with_cache(Arc::new(ShardedCache::new(
+            config.performance.cache_bytes()?,
+        )));
 
     let mut files = scanner
         .scan(&args.path)
@@ -31,14 +38,8 @@ pub fn execute(args: ShowArgs) -> Result<()> {
         return Ok(());
     }
 
-    // Parse files if needed
-    let parser = Parser::new();
-    let mut parsed_files = Vec::new();
-
-    for file in &files {
-        let parsed = parser.parse(file)?;
-        parsed_files.push((file, parsed));
-    }
+    let mut resolver = ImportResolver::from_config(&args.path, &config);
+    resolver.resolve_all(&mut files)?;
 
     // Format and display
     match args.format {
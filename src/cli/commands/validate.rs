@@ -1,8 +1,35 @@
+use super::diagnose::{apply_fixes, check_thresholds, load, Caches};
+use crate::analyzer::{Diagnostics, RuleRegistry};
 use crate::cli::args::ValidateArgs;
+use crate::core::Config;
+use crate::formatter::{ReportFormatter, TextReport};
 use anyhow::Result;
+use std::sync::Arc;
 
-pub fn execute(_args: ValidateArgs) -> Result<()> {
-    // TODO: Implement validate command
-    println!("Validate command not yet implemented");
-    Ok(())
+pub fn execute(args: ValidateArgs) -> Result<()> {
+    let config = Config::load(&args.path)?;
+    let caches = Caches::new(&config)?;
+    let diagnostics = Diagnostics::new()
+        .with_registry(RuleRegistry::with_custom_rules(&config, &args.path, &[])?)
+        .with_config(config.clone())
+        .with_parse_cache(Arc::clone(&caches.parsed))
+        .with_project_root(&args.path)
+        .with_all_rules(args.strict || args.all_rules)
+        .with_warnings_as_errors(args.strict || args.warnings_as_errors);
+
+    let (files, graph) = load(&args.path, false, &config, &caches.files)?;
+    let mut result = diagnostics.check_with_graph(&files, &graph)?;
+
+    if args.fix {
+        apply_fixes(&result, args.dry_run)?;
+        if args.dry_run {
+            return Ok(());
+        }
+        let (files, graph) = load(&args.path, false, &config, &caches.files)?;
+        result = diagnostics.check_with_graph(&files, &graph)?;
+    }
+
+    print!("{}", TextReport::new().render(&result)?);
+
+    check_thresholds(&result, args.fail_on.into(), args.max_warnings)
 }
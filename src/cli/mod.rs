@@ -4,6 +4,27 @@ pub mod commands;
 use clap::{Parser, Subcommand};
 use std::path::PathBuf;
 
+/// Process exit codes of `ccat`.
+pub mod exit_code {
+    /// No findings at or above the failure threshold.
+    pub const SUCCESS: i32 = 0;
+    /// Findings at or above `--fail-on`, or more warnings than `--max-warnings`.
+    pub const FINDINGS: i32 = 1;
+    /// Invalid arguments or configuration, or an I/O failure.
+    pub const ERROR: i32 = 2;
+}
+
+const EXIT_CODES_HELP: &str = "Exit codes:
+  0  No findings at or above the --fail-on threshold
+  1  Findings at or above the threshold, or more than --max-warnings warnings
+  2  Invalid arguments or configuration, or an I/O error";
+
+/// Returned by commands whose checks found problems, so `main` can exit with
+/// `exit_code::FINDINGS` instead of `exit_code::ERROR`.
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+pub struct CheckFailed(pub String);
+
 #[derive(Debug, Parser)]
 #[command(name = "ccat")]
 #[command(about = "CLAUDE.md Context Analyzer", long_about = None)]
@@ -30,9 +51,12 @@ pub enum Commands {
     #[command(about = "Display memory files (default)")]
     Show(args::ShowArgs),
 
-    #[command(about = "Run diagnostics on configuration")]
+    #[command(about = "Run diagnostics on configuration", after_help = EXIT_CODES_HELP)]
     Diagnose(args::DiagnoseArgs),
 
+    #[command(about = "List and explain diagnostic rules")]
+    Rules(args::RulesArgs),
+
     #[command(about = "Search within context")]
     Search(args::SearchArgs),
 
@@ -45,11 +69,20 @@ pub enum Commands {
     #[command(about = "Export context to various formats")]
     Export(args::ExportArgs),
 
-    #[command(about = "Validate configuration")]
+    #[command(about = "Map a line of exported output back to its source")]
+    Locate(args::LocateArgs),
+
+    #[command(about = "Validate configuration", after_help = EXIT_CODES_HELP)]
     Validate(args::ValidateArgs),
 
     #[command(about = "Initialize a new CLAUDE.md file")]
     Init(args::InitArgs),
+
+    #[command(about = "Render the import graph")]
+    Graph(args::GraphArgs),
+
+    #[command(about = "Migrate CLAUDE.local.md to a home directory import")]
+    Migrate(args::MigrateArgs),
 }
 
 #[derive(Debug, Clone, Copy, clap::ValueEnum)]
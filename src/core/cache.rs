@@ -1,9 +1,14 @@
+use super::parser::{ParsedContent, Section};
+use super::{Import, MemoryFile};
 use anyhow::Result;
 use lru::LruCache;
 use sha2::{Digest, Sha256};
+use std::collections::hash_map::DefaultHasher;
 use std::fs;
+use std::hash::{Hash, Hasher};
 use std::num::NonZeroUsize;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
 use std::time::SystemTime;
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
@@ -101,3 +106,263 @@ impl ContentHashCache {
         Ok(format!("{:x}", hasher.finalize()))
     }
 }
+
+/// Approximate in-memory size of a cached value, used for byte-bounded eviction.
+pub trait Weighted {
+    fn weight(&self) -> usize;
+}
+
+impl Weighted for String {
+    fn weight(&self) -> usize {
+        self.len()
+    }
+}
+
+impl Weighted for Vec<u8> {
+    fn weight(&self) -> usize {
+        self.len()
+    }
+}
+
+impl Weighted for MemoryFile {
+    fn weight(&self) -> usize {
+        let imports: usize = self
+            .imports
+            .iter()
+            .map(|i| i.path.len() + std::mem::size_of::<Import>())
+            .sum();
+        self.content.len() + self.path.as_os_str().len() + imports
+    }
+}
+
+impl Weighted for ParsedContent {
+    fn weight(&self) -> usize {
+        let sections: usize = self
+            .sections
+            .iter()
+            .map(|s| s.title.len() + s.content.len() + std::mem::size_of::<Section>())
+            .sum();
+        sections + self.imports.len() * std::mem::size_of::<Import>()
+    }
+}
+
+const DEFAULT_SHARDS: usize = 16;
+
+/// Thread-safe variant of [`FileCache`] for use from rayon workers.
+///
+/// Entries are spread over independently locked LRU shards by path and the
+/// total size is bounded in bytes rather than entries.
+pub struct ShardedCache<T> {
+    shards: Vec<Mutex<CacheShard<T>>>,
+}
+
+struct CacheShard<T> {
+    entries: LruCache<CacheKey, Arc<T>>,
+    bytes: usize,
+    max_bytes: usize,
+}
+
+impl<T: Weighted> ShardedCache<T> {
+    pub fn new(max_bytes: u64) -> Self {
+        Self::with_shards(max_bytes, DEFAULT_SHARDS)
+    }
+
+    pub fn with_shards(max_bytes: u64, shards: usize) -> Self {
+        let shards = shards.max(1);
+        let max_bytes = usize::try_from(max_bytes).unwrap_or(usize::MAX) / shards;
+        Self {
+            shards: (0..shards)
+                .map(|_| {
+                    Mutex::new(CacheShard {
+                        entries: LruCache::unbounded(),
+                        bytes: 0,
+                        max_bytes,
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    pub fn get<P: AsRef<Path>>(&self, path: P) -> Result<Option<Arc<T>>> {
+        let key = CacheKey::from_path(path)?;
+        Ok(self.shard(&key).entries.get(&key).cloned())
+    }
+
+    pub fn insert<P: AsRef<Path>>(&self, path: P, value: T) -> Result<Arc<T>> {
+        let key = CacheKey::from_path(path)?;
+        Ok(self.insert_with_key(key, value))
+    }
+
+    /// Returns the cached value for `path`, computing and caching it on a miss.
+    ///
+    /// The lock is not held while `compute` runs, so concurrent misses for the
+    /// same file may compute it more than once.
+    pub fn get_or_insert_with<P, F>(&self, path: P, compute: F) -> Result<Arc<T>>
+    where
+        P: AsRef<Path>,
+        F: FnOnce() -> Result<T>,
+    {
+        let key = CacheKey::from_path(path)?;
+        if let Some(value) = self.shard(&key).entries.get(&key) {
+            return Ok(Arc::clone(value));
+        }
+
+        let value = compute()?;
+        Ok(self.insert_with_key(key, value))
+    }
+
+    pub fn remove<P: AsRef<Path>>(&self, path: P) -> Result<Option<Arc<T>>> {
+        let key = CacheKey::from_path(path)?;
+        let mut shard = self.shard(&key);
+        let removed = shard.entries.pop(&key);
+        if let Some(value) = &removed {
+            shard.bytes -= value.weight();
+        }
+        Ok(removed)
+    }
+
+    pub fn clear(&self) {
+        for shard in &self.shards {
+            let mut shard = lock(shard);
+            shard.entries.clear();
+            shard.bytes = 0;
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(|s| lock(s).entries.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Total weight of the cached values in bytes.
+    pub fn size_bytes(&self) -> usize {
+        self.shards.iter().map(|s| lock(s).bytes).sum()
+    }
+
+    fn insert_with_key(&self, key: CacheKey, value: T) -> Arc<T> {
+        let weight = value.weight();
+        let value = Arc::new(value);
+        let mut shard = self.shard(&key);
+
+        // Values larger than a whole shard are returned without being cached
+        if weight > shard.max_bytes {
+            return value;
+        }
+
+        if let Some(previous) = shard.entries.put(key, Arc::clone(&value)) {
+            shard.bytes -= previous.weight();
+        }
+        shard.bytes += weight;
+
+        while shard.bytes > shard.max_bytes {
+            match shard.entries.pop_lru() {
+                Some((_, evicted)) => shard.bytes -= evicted.weight(),
+                None => break,
+            }
+        }
+
+        value
+    }
+
+    fn shard(&self, key: &CacheKey) -> MutexGuard<'_, CacheShard<T>> {
+        // Shard by path only so every version of a file lands in the same shard
+        let mut hasher = DefaultHasher::new();
+        key.path.hash(&mut hasher);
+        let idx = (hasher.finish() as usize) % self.shards.len();
+        lock(&self.shards[idx])
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    // A panic while holding the lock cannot leave a shard inconsistent enough
+    // to matter for a cache, so recover from poisoning
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Parses sizes such as `100MB`, `512 KB` or `1048576` (binary units).
+pub fn parse_size(value: &str) -> Result<u64> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: f64 = number
+        .parse()
+        .map_err(|_| anyhow::anyhow!("Invalid size: {}", value))?;
+
+    let multiplier: u64 = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KB" | "KIB" => 1 << 10,
+        "M" | "MB" | "MIB" => 1 << 20,
+        "G" | "GB" | "GIB" => 1 << 30,
+        _ => anyhow::bail!("Invalid size unit in {}", value),
+    };
+
+    Ok((number * multiplier as f64) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evicts_least_recently_used_entries_past_the_byte_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths: Vec<PathBuf> = (0..3)
+            .map(|i| dir.path().join(format!("{}.md", i)))
+            .collect();
+        for path in &paths {
+            fs::write(path, "x").unwrap();
+        }
+
+        let cache = ShardedCache::with_shards(10, 1);
+        cache.insert(&paths[0], "aaaa".to_string()).unwrap();
+        cache.insert(&paths[1], "bbbb".to_string()).unwrap();
+        // Touch the first entry so the second is evicted instead
+        assert!(cache.get(&paths[0]).unwrap().is_some());
+        cache.insert(&paths[2], "cccc".to_string()).unwrap();
+
+        assert_eq!((cache.len(), cache.size_bytes()), (2, 8));
+        assert!(cache.get(&paths[1]).unwrap().is_none());
+
+        // Replacing an entry accounts for the old value
+        cache.insert(&paths[0], "aa".to_string()).unwrap();
+        assert_eq!((cache.len(), cache.size_bytes()), (2, 6));
+
+        // Values larger than the shard are returned but not cached
+        let large = cache.insert(&paths[1], "b".repeat(11)).unwrap();
+        assert_eq!(large.len(), 11);
+        assert_eq!((cache.len(), cache.size_bytes()), (2, 6));
+
+        cache.remove(&paths[2]).unwrap();
+        assert_eq!((cache.len(), cache.size_bytes()), (1, 2));
+    }
+
+    #[test]
+    fn entries_of_modified_files_are_misses() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("CLAUDE.md");
+        fs::write(&path, "one").unwrap();
+
+        let cache = ShardedCache::new(1 << 20);
+        cache.insert(&path, "one".to_string()).unwrap();
+        fs::write(&path, "longer").unwrap();
+        assert!(cache.get(&path).unwrap().is_none());
+    }
+
+    #[test]
+    fn parses_sizes_with_binary_units() {
+        assert_eq!(parse_size("1048576").unwrap(), 1 << 20);
+        assert_eq!(parse_size("100MB").unwrap(), 100 << 20);
+        assert_eq!(parse_size("512 KB").unwrap(), 512 << 10);
+        assert_eq!(parse_size("1.5g").unwrap(), 3 << 29);
+        assert_eq!(parse_size("64B").unwrap(), 64);
+        assert!(parse_size("MB").is_err());
+        assert!(parse_size("10 TB").is_err());
+    }
+}
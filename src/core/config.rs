@@ -0,0 +1,137 @@
+use super::cache::parse_size;
+use super::policy::ImportPolicy;
+use super::resolver::expand_home;
+use crate::analyzer::rules::custom::CustomRuleSpec;
+use crate::analyzer::rules::script::ScriptRuleSpec;
+use crate::analyzer::DiagnosticLevel;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const PROJECT_CONFIG_FILE: &str = ".ccat.toml";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub imports: ImportConfig,
+    pub diagnostics: DiagnosticsConfig,
+    pub performance: PerformanceConfig,
+    /// File the configuration was loaded from, if any.
+    #[serde(skip)]
+    pub path: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImportConfig {
+    pub policy: ImportPolicy,
+    /// Maps an alias to a directory, so `@shared/style.md` resolves to
+    /// `<dir>/style.md`. Relative directories are taken from the project root.
+    pub aliases: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiagnosticsConfig {
+    /// Shorthand for `all_rules` and `warnings_as_errors`.
+    pub strict: bool,
+    /// Runs opt-in rules too.
+    pub all_rules: bool,
+    pub warnings_as_errors: bool,
+    /// Rule ids to run even if they are opt-in.
+    pub enable: Vec<String>,
+    /// Rule ids to skip.
+    pub disable: Vec<String>,
+    /// Per-rule severity overrides, e.g. `large-file = "error"`.
+    pub severity: BTreeMap<String, DiagnosticLevel>,
+    /// Custom rule files (TOML or YAML), relative to the project root.
+    pub rule_files: Vec<String>,
+    /// Custom rules defined inline with `[[diagnostics.rules]]`.
+    pub rules: Vec<CustomRuleSpec>,
+    /// Rules implemented as Rhai scripts, with `[[diagnostics.scripts]]`.
+    pub scripts: Vec<ScriptRuleSpec>,
+    pub context_budget: ContextBudgetConfig,
+}
+
+/// Token limits for the context Claude Code loads in each directory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ContextBudgetConfig {
+    pub warning_tokens: usize,
+    pub error_tokens: usize,
+}
+
+impl Default for ContextBudgetConfig {
+    fn default() -> Self {
+        Self {
+            warning_tokens: 10_000,
+            error_tokens: 25_000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PerformanceConfig {
+    pub parallel: bool,
+    /// Memory budget for file and parse caches, e.g. `"100MB"`.
+    pub cache_size: String,
+}
+
+impl Default for PerformanceConfig {
+    fn default() -> Self {
+        Self {
+            parallel: true,
+            cache_size: "100MB".to_string(),
+        }
+    }
+}
+
+impl PerformanceConfig {
+    pub fn cache_bytes(&self) -> Result<u64> {
+        parse_size(&self.cache_size)
+            .with_context(|| format!("Invalid performance.cache_size: {}", self.cache_size))
+    }
+}
+
+impl ImportConfig {
+    /// Alias directories resolved against `project_root`.
+    pub fn alias_dirs(&self, project_root: &Path) -> BTreeMap<String, PathBuf> {
+        self.aliases
+            .iter()
+            .map(|(name, dir)| (name.clone(), project_root.join(expand_home(dir))))
+            .collect()
+    }
+}
+
+impl Config {
+    /// Loads `.ccat.toml` from the project root, falling back to the user
+    /// config at `~/.config/ccat/config.toml` and then to defaults.
+    pub fn load<P: AsRef<Path>>(project_root: P) -> Result<Self> {
+        let project_config = project_root.as_ref().join(PROJECT_CONFIG_FILE);
+        if project_config.is_file() {
+            return Self::from_file(&project_config);
+        }
+
+        match Self::user_config_path() {
+            Some(user_config) if user_config.is_file() => Self::from_file(&user_config),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config: {}", path.display()))?;
+        let mut config: Self = toml::from_str(&content)
+            .with_context(|| format!("Invalid config: {}", path.display()))?;
+        config.path = Some(path.to_path_buf());
+        Ok(config)
+    }
+
+    fn user_config_path() -> Option<PathBuf> {
+        dirs::home_dir().map(|home| home.join(".config").join("ccat").join("config.toml"))
+    }
+}
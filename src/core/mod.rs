@@ -1,9 +1,15 @@
 pub mod cache;
+pub mod config;
 pub mod parser;
+pub mod policy;
 pub mod resolver;
 pub mod scanner;
+pub mod source_map;
 
 use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use policy::PolicyViolation;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
@@ -16,6 +22,41 @@ pub struct MemoryFile {
     pub metadata: FileMetadata,
 }
 
+impl MemoryFile {
+    /// Sort key for the memory hierarchy. Files that sort later are more
+    /// specific and take precedence when their instructions conflict.
+    pub fn precedence(&self) -> (u8, usize) {
+        let rank = match self.file_type {
+            MemoryType::UserMemory => 0,
+            MemoryType::ProjectMemory => 1,
+            MemoryType::LocalMemory => 2,
+            MemoryType::SubdirMemory => 3,
+        };
+        (rank, self.path.components().count())
+    }
+
+    /// In-memory file for unit tests; imports are extracted but not resolved.
+    #[cfg(test)]
+    pub(crate) fn from_content(
+        path: impl Into<PathBuf>,
+        file_type: MemoryType,
+        content: &str,
+    ) -> Self {
+        Self {
+            path: path.into(),
+            file_type,
+            content: content.to_string(),
+            imports: scanner::extract_imports(content),
+            metadata: FileMetadata {
+                size: content.len() as u64,
+                modified: Utc::now(),
+                hash: String::new(),
+                line_count: content.lines().count(),
+            },
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum MemoryType {
     ProjectMemory, // ./CLAUDE.md
@@ -29,6 +70,124 @@ pub struct Import {
     pub path: String,
     pub line_number: usize,
     pub resolved_path: Option<PathBuf>,
+    #[serde(default)]
+    pub kind: ImportKind,
+    /// Files matched by a glob or directory import, in sorted order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub resolved_paths: Vec<PathBuf>,
+    /// Targets that exist but were refused by the import policy.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub policy_violations: Vec<PolicyViolation>,
+    /// Why a glob import's pattern could not be parsed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern_error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selector: Option<ImportSelector>,
+    /// Inclusive line range of the target picked out by `selector`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selected_lines: Option<(usize, usize)>,
+    /// Configured alias the import was resolved through.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImportKind {
+    /// `@docs/guide.md`
+    #[default]
+    File,
+    /// `@docs/conventions/*.md`
+    Glob,
+    /// `@docs/conventions/` (all Markdown files in the directory)
+    Directory,
+}
+
+/// Restricts a file import to part of the target.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImportSelector {
+    /// `@docs/guide.md#testing`: the heading and everything nested under it
+    Section(String),
+    /// `@src/config.rs:10-40`: an inclusive, 1-based line range
+    Lines { start: usize, end: usize },
+}
+
+impl Import {
+    pub fn new(path: impl Into<String>, line_number: usize) -> Self {
+        let path = path.into();
+        let kind = if path.contains(['*', '?', '[']) {
+            ImportKind::Glob
+        } else if path.ends_with('/') {
+            ImportKind::Directory
+        } else {
+            ImportKind::File
+        };
+        let selector = match kind {
+            ImportKind::File => parse_selector(&path).map(|(_, selector)| selector),
+            ImportKind::Glob | ImportKind::Directory => None,
+        };
+
+        Self {
+            path,
+            line_number,
+            resolved_path: None,
+            kind,
+            resolved_paths: Vec::new(),
+            policy_violations: Vec::new(),
+            pattern_error: None,
+            selector,
+            selected_lines: None,
+            alias: None,
+        }
+    }
+
+    /// The path portion of the import, without any `#section` or `:lines` selector.
+    pub fn file_path(&self) -> &str {
+        match self.selector {
+            Some(_) => parse_selector(&self.path).map_or(&self.path, |(file, _)| file),
+            None => &self.path,
+        }
+    }
+
+    pub fn is_glob(&self) -> bool {
+        self.kind != ImportKind::File
+    }
+
+    pub fn is_resolved(&self) -> bool {
+        match self.kind {
+            ImportKind::File => {
+                self.resolved_path.is_some()
+                    && (self.selector.is_none() || self.selected_lines.is_some())
+            }
+            ImportKind::Glob | ImportKind::Directory => !self.resolved_paths.is_empty(),
+        }
+    }
+
+    /// All files this import resolved to.
+    pub fn targets(&self) -> impl Iterator<Item = &PathBuf> {
+        self.resolved_path.iter().chain(self.resolved_paths.iter())
+    }
+}
+
+static LINE_RANGE_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(.+):(\d+)(?:-(\d+))?$").unwrap());
+
+/// Splits a `:lines` or `#section` selector off an import path. A section is
+/// split at the last `#`; the resolver falls back to the whole path when that
+/// names an existing file, as in `@notes/#todo.md`.
+fn parse_selector(path: &str) -> Option<(&str, ImportSelector)> {
+    if let Some(captures) = LINE_RANGE_REGEX.captures(path) {
+        let start: usize = captures[2].parse().ok()?;
+        let end = match captures.get(3) {
+            Some(end) => end.as_str().parse().ok()?,
+            None => start,
+        };
+        let file = captures.get(1)?.as_str();
+        return Some((file, ImportSelector::Lines { start, end }));
+    }
+
+    let (file, section) = path.rsplit_once('#')?;
+    (!file.is_empty() && !section.is_empty())
+        .then(|| (file, ImportSelector::Section(section.to_string())))
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -39,7 +198,57 @@ pub struct FileMetadata {
     pub line_count: usize,
 }
 
-pub use cache::FileCache;
-pub use parser::Parser;
-pub use resolver::ImportResolver;
+pub use cache::{FileCache, ShardedCache};
+pub use config::Config;
+pub use parser::{Parser, Section};
+pub use policy::ImportPolicy;
+pub use resolver::{CircularImport, ImportGraph, ImportResolver, MAX_IMPORT_DEPTH};
 pub use scanner::Scanner;
+pub use source_map::{ExpandedContext, SourceLocation, SourceMap};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_line_and_section_selectors() {
+        assert_eq!(
+            parse_selector("src/config.rs:10-40"),
+            Some((
+                "src/config.rs",
+                ImportSelector::Lines { start: 10, end: 40 }
+            ))
+        );
+        assert_eq!(
+            parse_selector("src/config.rs:7"),
+            Some(("src/config.rs", ImportSelector::Lines { start: 7, end: 7 }))
+        );
+        assert_eq!(
+            parse_selector("docs/guide.md#testing"),
+            Some((
+                "docs/guide.md",
+                ImportSelector::Section("testing".to_string())
+            ))
+        );
+        assert_eq!(
+            parse_selector("notes/#todo.md#open"),
+            Some((
+                "notes/#todo.md",
+                ImportSelector::Section("open".to_string())
+            ))
+        );
+        assert_eq!(parse_selector("docs/guide.md"), None);
+        assert_eq!(parse_selector("#testing"), None);
+        assert_eq!(parse_selector("docs/guide.md#"), None);
+    }
+
+    #[test]
+    fn globs_and_directories_have_no_selector() {
+        let import = Import::new("docs/guide.md#testing", 1);
+        assert_eq!(import.file_path(), "docs/guide.md");
+        assert!(!import.is_glob());
+
+        assert_eq!(Import::new("docs/*.md", 1).selector, None);
+        assert_eq!(Import::new("docs/", 1).selector, None);
+    }
+}
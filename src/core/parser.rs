@@ -1,8 +1,10 @@
+use super::cache::ShardedCache;
 use super::{Import, MemoryFile};
 use anyhow::Result;
 use once_cell::sync::Lazy;
 use regex::Regex;
 use std::collections::HashMap;
+use std::sync::Arc;
 
 static IMPORT_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\{\{import\s+([^\}]+)\}\}").unwrap());
 
@@ -32,10 +34,47 @@ pub struct ContentMetadata {
     pub link_count: usize,
 }
 
+impl ParsedContent {
+    /// Line range of the section titled `name` (matched by title or by its
+    /// `#anchor` slug), including every nested subsection.
+    pub fn section_range(&self, name: &str) -> Option<(usize, usize)> {
+        let wanted = slugify(name);
+        let idx = self
+            .sections
+            .iter()
+            .position(|s| s.title.eq_ignore_ascii_case(name) || slugify(&s.title) == wanted)?;
+        let section = &self.sections[idx];
+
+        let end = self.sections[idx + 1..]
+            .iter()
+            .find(|s| s.level <= section.level)
+            .map(|s| s.line_start - 1)
+            .or_else(|| self.sections.last().map(|s| s.line_end))
+            .unwrap_or(section.line_end);
+
+        Some((section.line_start, end))
+    }
+}
+
+/// GitHub-style heading anchor: lowercase, spaces to dashes, punctuation dropped.
+pub fn slugify(title: &str) -> String {
+    title
+        .trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
 pub struct Parser {
     extract_sections: bool,
     #[allow(dead_code)]
     resolve_imports: bool,
+    cache: Option<Arc<ShardedCache<ParsedContent>>>,
 }
 
 impl Parser {
@@ -43,11 +82,30 @@ impl Parser {
         Self {
             extract_sections: true,
             resolve_imports: true,
+            cache: None,
         }
     }
 
+    pub fn with_cache(mut self, cache: Arc<ShardedCache<ParsedContent>>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
     pub fn parse(&self, file: &MemoryFile) -> Result<ParsedContent> {
-        let lines: Vec<&str> = file.content.lines().collect();
+        // Files that no longer exist on disk cannot be keyed, so parse them uncached
+        match &self.cache {
+            Some(cache) => {
+                match cache.get_or_insert_with(&file.path, || self.parse_content(&file.content)) {
+                    Ok(parsed) => Ok(ParsedContent::clone(&parsed)),
+                    Err(_) => self.parse_content(&file.content),
+                }
+            }
+            None => self.parse_content(&file.content),
+        }
+    }
+
+    pub fn parse_content(&self, content: &str) -> Result<ParsedContent> {
+        let lines: Vec<&str> = content.lines().collect();
         let mut sections = Vec::new();
         let mut imports = Vec::new();
         let mut current_section: Option<(String, usize, usize, Vec<String>)> = None;
@@ -71,11 +129,7 @@ impl Parser {
             // Extract imports
             if let Some(captures) = IMPORT_REGEX.captures(line) {
                 if let Some(import_path) = captures.get(1) {
-                    imports.push(Import {
-                        path: import_path.as_str().trim().to_string(),
-                        line_number,
-                        resolved_path: None,
-                    });
+                    imports.push(Import::new(import_path.as_str().trim(), line_number));
                 }
             }
 
@@ -0,0 +1,103 @@
+use glob::Pattern;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Restricts which files memory files may import.
+///
+/// Relative roots are resolved against the project root; `~/` expands to the
+/// home directory. An empty `allowed_extensions` list allows every extension.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImportPolicy {
+    pub allowed_roots: Vec<String>,
+    pub denied_patterns: Vec<String>,
+    pub allowed_extensions: Vec<String>,
+}
+
+impl Default for ImportPolicy {
+    fn default() -> Self {
+        Self {
+            allowed_roots: vec![".".to_string(), "~/.claude".to_string()],
+            denied_patterns: [
+                "**/.ssh/**",
+                "**/.aws/**",
+                "**/.gnupg/**",
+                "**/.env",
+                "**/.env.*",
+                "**/*.pem",
+                "**/*.key",
+                "**/id_rsa*",
+                "**/id_ed25519*",
+            ]
+            .iter()
+            .map(|p| p.to_string())
+            .collect(),
+            allowed_extensions: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PolicyViolation {
+    pub path: PathBuf,
+    pub reason: String,
+}
+
+impl std::fmt::Display for PolicyViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.path.display(), self.reason)
+    }
+}
+
+impl ImportPolicy {
+    /// Checks a canonicalized import target against the policy.
+    pub fn check(&self, path: &Path, project_root: &Path) -> Result<(), PolicyViolation> {
+        let violation = |reason: String| PolicyViolation {
+            path: path.to_path_buf(),
+            reason,
+        };
+
+        let path_str = path.to_string_lossy();
+        for pattern in &self.denied_patterns {
+            if Pattern::new(pattern).is_ok_and(|p| p.matches(&path_str)) {
+                return Err(violation(format!("matches denied pattern `{}`", pattern)));
+            }
+        }
+
+        if !self.allowed_roots.is_empty()
+            && !self
+                .allowed_roots
+                .iter()
+                .any(|root| path.starts_with(resolve_root(root, project_root)))
+        {
+            return Err(violation("outside allowed roots".to_string()));
+        }
+
+        if !self.allowed_extensions.is_empty() {
+            let extension = path
+                .extension()
+                .map(|e| e.to_string_lossy().to_lowercase())
+                .unwrap_or_default();
+            let allowed = self
+                .allowed_extensions
+                .iter()
+                .any(|e| e.trim_start_matches('.').eq_ignore_ascii_case(&extension));
+            if !allowed {
+                return Err(violation(format!(
+                    "extension `.{}` is not allowed",
+                    extension
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn resolve_root(root: &str, project_root: &Path) -> PathBuf {
+    let root = match (root.strip_prefix('~'), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest.trim_start_matches('/')),
+        _ => project_root.join(root),
+    };
+    root.canonicalize().unwrap_or(root)
+}
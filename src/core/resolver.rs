@@ -1,12 +1,22 @@
-use super::MemoryFile;
+use super::config::Config;
+use super::parser::Parser;
+use super::policy::ImportPolicy;
+use super::scanner::extract_imports;
+use super::source_map::ExpandedContext;
+use super::{Import, ImportKind, ImportSelector, MemoryFile};
 use anyhow::{bail, Result};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
 use std::fs;
 use std::path::{Path, PathBuf};
 
+/// Maximum number of import hops Claude Code follows.
+pub const MAX_IMPORT_DEPTH: usize = 5;
+
 pub struct ImportResolver {
     base_path: PathBuf,
-    resolved_cache: HashMap<String, PathBuf>,
+    resolved_cache: HashMap<(PathBuf, String), PathBuf>,
+    policy: ImportPolicy,
+    aliases: BTreeMap<String, PathBuf>,
     #[allow(dead_code)]
     visited: HashSet<PathBuf>,
 }
@@ -16,10 +26,42 @@ impl ImportResolver {
         Self {
             base_path: base_path.as_ref().to_path_buf(),
             resolved_cache: HashMap::new(),
+            policy: ImportPolicy::default(),
+            aliases: BTreeMap::new(),
             visited: HashSet::new(),
         }
     }
 
+    /// Creates a resolver using the import policy and aliases from `config`.
+    pub fn from_config<P: AsRef<Path>>(base_path: P, config: &Config) -> Self {
+        let base_path = base_path.as_ref();
+        let aliases = config.imports.alias_dirs(base_path);
+
+        // Configured alias directories are trusted import roots
+        let mut policy = config.imports.policy.clone();
+        if !policy.allowed_roots.is_empty() {
+            policy.allowed_roots.extend(
+                aliases
+                    .values()
+                    .map(|dir| dir.to_string_lossy().to_string()),
+            );
+        }
+
+        Self::new(base_path)
+            .with_policy(policy)
+            .with_aliases(aliases)
+    }
+
+    pub fn with_policy(mut self, policy: ImportPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn with_aliases(mut self, aliases: BTreeMap<String, PathBuf>) -> Self {
+        self.aliases = aliases;
+        self
+    }
+
     pub fn resolve_imports(&mut self, file: &mut MemoryFile) -> Result<()> {
         let file_dir = file
             .path
@@ -27,14 +69,102 @@ impl ImportResolver {
             .ok_or_else(|| anyhow::anyhow!("Invalid file path"))?;
 
         for import in &mut file.imports {
-            if let Some(resolved) = self.resolve_import_path(&import.path, file_dir)? {
-                import.resolved_path = Some(resolved);
+            self.resolve_import(import, file_dir)?;
+        }
+
+        Ok(())
+    }
+
+    /// Resolves a single import relative to the directory of the importing file.
+    ///
+    /// Targets refused by the import policy are recorded in
+    /// `policy_violations` instead of being resolved, and malformed glob
+    /// patterns in `pattern_error`.
+    pub fn resolve_import(&mut self, import: &mut Import, base_dir: &Path) -> Result<()> {
+        import.alias = None;
+        let path = import.file_path().to_string();
+
+        import.pattern_error = match import.kind {
+            ImportKind::Glob => glob::Pattern::new(&path).err().map(|err| err.to_string()),
+            ImportKind::File | ImportKind::Directory => None,
+        };
+        if import.pattern_error.is_some() {
+            import.resolved_paths.clear();
+            import.policy_violations.clear();
+            return Ok(());
+        }
+
+        // Aliases take precedence over the regular candidate list
+        let mut targets = Vec::new();
+        if let Some((alias, aliased)) = self.apply_alias(&path) {
+            targets = self.resolve_targets(import.kind, &aliased, base_dir)?;
+            if !targets.is_empty() {
+                import.alias = Some(alias);
+            }
+        }
+        if targets.is_empty() {
+            targets = self.resolve_targets(import.kind, &path, base_dir)?;
+        }
+        // `#` may be part of the file name rather than a section selector
+        if targets.is_empty() && matches!(import.selector, Some(ImportSelector::Section(_))) {
+            targets = self.resolve_targets(ImportKind::File, &import.path, base_dir)?;
+            if !targets.is_empty() {
+                import.selector = None;
             }
         }
 
+        let project_root = canonical_path(&self.base_path);
+        let mut allowed = Vec::new();
+        import.policy_violations.clear();
+        for target in targets {
+            match self.policy.check(&target, &project_root) {
+                Ok(()) => allowed.push(target),
+                Err(violation) => import.policy_violations.push(violation),
+            }
+        }
+
+        match import.kind {
+            ImportKind::File => import.resolved_path = allowed.pop(),
+            ImportKind::Glob | ImportKind::Directory => import.resolved_paths = allowed,
+        }
+
+        import.selected_lines = match (&import.selector, &import.resolved_path) {
+            (Some(selector), Some(resolved)) => select_lines(resolved, selector)?,
+            _ => None,
+        };
+
         Ok(())
     }
 
+    fn resolve_targets(
+        &mut self,
+        kind: ImportKind,
+        path: &str,
+        base_dir: &Path,
+    ) -> Result<Vec<PathBuf>> {
+        Ok(match kind {
+            ImportKind::File => self
+                .resolve_import_path(path, base_dir)?
+                .into_iter()
+                .collect(),
+            ImportKind::Glob => self.resolve_glob(path, base_dir)?,
+            ImportKind::Directory => self.resolve_glob(&format!("{}*.md", path), base_dir)?,
+        })
+    }
+
+    /// Rewrites `alias/rest` to `<alias dir>/rest` when the first path
+    /// component names a configured alias.
+    fn apply_alias(&self, path: &str) -> Option<(String, String)> {
+        let (name, rest) = path.split_once('/').unwrap_or((path, ""));
+        let dir = self.aliases.get(name)?;
+        let aliased = if rest.is_empty() {
+            format!("{}/", dir.display())
+        } else {
+            dir.join(rest).to_string_lossy().to_string()
+        };
+        Some((name.to_string(), aliased))
+    }
+
     pub fn resolve_all(&mut self, files: &mut [MemoryFile]) -> Result<()> {
         for file in files {
             self.resolve_imports(file)?;
@@ -43,33 +173,42 @@ impl ImportResolver {
     }
 
     pub fn check_circular_imports(&self, files: &[MemoryFile]) -> Result<Vec<CircularImport>> {
-        let mut circular_imports = Vec::new();
-        let mut import_graph: HashMap<PathBuf, Vec<PathBuf>> = HashMap::new();
+        Ok(ImportGraph::from_files(files).cycles())
+    }
 
-        // Build import graph
-        for file in files {
-            let mut deps = Vec::new();
-            for import in &file.imports {
-                if let Some(resolved) = &import.resolved_path {
-                    deps.push(resolved.clone());
-                }
+    /// Builds the full import graph, following imports of imported files transitively.
+    pub fn build_graph(&mut self, files: &[MemoryFile]) -> Result<ImportGraph> {
+        let mut graph = ImportGraph::from_files(files);
+        let mut queue: VecDeque<PathBuf> = graph
+            .nodes()
+            .flat_map(|node| graph.dependencies(node).to_vec())
+            .collect();
+        let mut seen: HashSet<PathBuf> = files.iter().map(|f| canonical_path(&f.path)).collect();
+
+        while let Some(path) = queue.pop_front() {
+            if !seen.insert(path.clone()) {
+                continue;
             }
-            import_graph.insert(file.path.clone(), deps);
-        }
 
-        // Check for cycles using DFS
-        for path in import_graph.keys() {
-            let mut visited = HashSet::new();
-            let mut stack = Vec::new();
+            let content = match fs::read_to_string(&path) {
+                Ok(content) => content,
+                Err(_) => continue,
+            };
+            let file_dir = path.parent().unwrap_or(Path::new("."));
 
-            if let Some(cycle) = Self::find_cycle(path, &import_graph, &mut visited, &mut stack) {
-                circular_imports.push(CircularImport {
-                    cycle: cycle.clone(),
-                });
+            for mut import in extract_imports(&content) {
+                self.resolve_import(&mut import, file_dir)?;
+                for resolved in import.targets() {
+                    graph.add_edge(&path, resolved, import.line_number);
+                    queue.push_back(resolved.clone());
+                }
+                if !import.policy_violations.is_empty() {
+                    graph.denied.push((path.clone(), import));
+                }
             }
         }
 
-        Ok(circular_imports)
+        Ok(graph)
     }
 
     fn resolve_import_path(
@@ -78,10 +217,13 @@ impl ImportResolver {
         base_dir: &Path,
     ) -> Result<Option<PathBuf>> {
         // Check cache
-        if let Some(cached) = self.resolved_cache.get(import_path) {
+        let cache_key = (base_dir.to_path_buf(), import_path.to_string());
+        if let Some(cached) = self.resolved_cache.get(&cache_key) {
             return Ok(Some(cached.clone()));
         }
 
+        let import_path = &expand_home(import_path);
+
         // Try different resolution strategies
         let candidates = vec![
             // Relative to current file
@@ -98,8 +240,7 @@ impl ImportResolver {
         for candidate in candidates {
             if candidate.exists() && candidate.is_file() {
                 let canonical = candidate.canonicalize()?;
-                self.resolved_cache
-                    .insert(import_path.to_string(), canonical.clone());
+                self.resolved_cache.insert(cache_key, canonical.clone());
                 return Ok(Some(canonical));
             }
         }
@@ -107,74 +248,636 @@ impl ImportResolver {
         Ok(None)
     }
 
-    fn find_cycle(
-        current: &Path,
-        graph: &HashMap<PathBuf, Vec<PathBuf>>,
-        visited: &mut HashSet<PathBuf>,
-        stack: &mut Vec<PathBuf>,
-    ) -> Option<Vec<PathBuf>> {
-        if stack.contains(&current.to_path_buf()) {
-            // Found a cycle
-            let pos = stack.iter().position(|p| p == current).unwrap();
-            return Some(stack[pos..].to_vec());
+    /// Expands a glob pattern, trying the importing file's directory first and
+    /// then the project root. Matches are sorted for deterministic expansion.
+    fn resolve_glob(&mut self, pattern: &str, base_dir: &Path) -> Result<Vec<PathBuf>> {
+        let pattern = expand_home(pattern);
+        let bases = if Path::new(&pattern).is_absolute() {
+            vec![PathBuf::from(&pattern)]
+        } else {
+            vec![base_dir.join(&pattern), self.base_path.join(&pattern)]
+        };
+
+        for base in bases {
+            // The base directory may itself contain pattern characters
+            let Ok(paths) = glob::glob(&base.to_string_lossy()) else {
+                continue;
+            };
+            let mut matches: Vec<PathBuf> = paths
+                .filter_map(|entry| entry.ok())
+                .filter(|path| path.is_file())
+                .filter_map(|path| path.canonicalize().ok())
+                .collect();
+
+            if !matches.is_empty() {
+                matches.sort();
+                matches.dedup();
+                return Ok(matches);
+            }
+        }
+
+        Ok(Vec::new())
+    }
+
+    pub fn expand_imports(&mut self, file: &MemoryFile, max_depth: usize) -> Result<String> {
+        Ok(self.expand(file, max_depth)?.content)
+    }
+
+    /// Expands imports of `file` recursively up to `max_depth` hops, recording
+    /// where every output line came from.
+    pub fn expand(&mut self, file: &MemoryFile, max_depth: usize) -> Result<ExpandedContext> {
+        let mut expansion = Expansion::new(max_depth);
+        self.expand_into(
+            &file.path,
+            &file.content,
+            &file.imports,
+            None,
+            0,
+            &mut expansion,
+        )?;
+        Ok(expansion.out)
+    }
+
+    /// Expands several memory files into one document, each preceded by a
+    /// `<!-- Source: ... -->` header and separated by a blank line.
+    pub fn expand_all(
+        &mut self,
+        files: &[MemoryFile],
+        max_depth: usize,
+    ) -> Result<ExpandedContext> {
+        let mut expansion = Expansion::new(max_depth);
+
+        for (i, file) in files.iter().enumerate() {
+            if i > 0 {
+                let previous = &files[i - 1];
+                let last_line = previous.content.lines().count();
+                expansion.out.push_generated("", &previous.path, last_line);
+            }
+            expansion.out.push_generated(
+                &format!("<!-- Source: {} -->", file.path.display()),
+                &file.path,
+                1,
+            );
+            self.expand_into(
+                &file.path,
+                &file.content,
+                &file.imports,
+                None,
+                0,
+                &mut expansion,
+            )?;
+        }
+
+        Ok(expansion.out)
+    }
+
+    fn expand_into(
+        &mut self,
+        path: &Path,
+        content: &str,
+        imports: &[Import],
+        lines: Option<(usize, usize)>,
+        depth: usize,
+        expansion: &mut Expansion,
+    ) -> Result<()> {
+        expansion.ancestors.push(canonical_path(path));
+
+        let (first, last) = lines.unwrap_or((1, usize::MAX));
+        for (idx, line) in content.lines().enumerate() {
+            let line_number = idx + 1;
+            if line_number < first || line_number > last {
+                continue;
+            }
+
+            let line_imports: Vec<&Import> = if depth < expansion.max_depth {
+                imports
+                    .iter()
+                    .filter(|i| i.line_number == line_number && i.is_resolved())
+                    .collect()
+            } else {
+                Vec::new()
+            };
+
+            // `{{import}}` directives are replaced; `@path` references are kept
+            // and followed by the imported content.
+            let is_directive = line.trim_start().starts_with("{{import");
+            if line_imports.is_empty() || !is_directive {
+                expansion.out.push_line(line, path, line_number);
+            }
+
+            for import in line_imports {
+                for target in import.targets() {
+                    // Following an import back into its own chain would never end
+                    if expansion.ancestors.contains(&canonical_path(target)) {
+                        expansion.out.push_generated(
+                            &format!("<!-- Circular import: {} -->", import_label(target, import)),
+                            path,
+                            line_number,
+                        );
+                        continue;
+                    }
+                    let imported = match fs::read_to_string(target) {
+                        Ok(imported) => imported,
+                        Err(_) => continue,
+                    };
+
+                    expansion.out.push_generated(
+                        &format!("<!-- Import from: {} -->", import_label(target, import)),
+                        path,
+                        line_number,
+                    );
+                    if is_markdown(target) {
+                        let target_dir = target.parent().unwrap_or(Path::new("."));
+                        let mut nested = extract_imports(&imported);
+                        for nested_import in &mut nested {
+                            self.resolve_import(nested_import, target_dir)?;
+                        }
+                        self.expand_into(
+                            target,
+                            &imported,
+                            &nested,
+                            import.selected_lines,
+                            depth + 1,
+                            expansion,
+                        )?;
+                    } else {
+                        push_fenced(
+                            target,
+                            &imported,
+                            import.selected_lines,
+                            path,
+                            line_number,
+                            expansion,
+                        );
+                    }
+                    expansion
+                        .out
+                        .push_generated("<!-- End import -->", path, line_number);
+                }
+            }
+        }
+
+        expansion.ancestors.pop();
+        Ok(())
+    }
+}
+
+/// State threaded through a single expansion run.
+struct Expansion {
+    max_depth: usize,
+    ancestors: Vec<PathBuf>,
+    out: ExpandedContext,
+}
+
+impl Expansion {
+    fn new(max_depth: usize) -> Self {
+        Self {
+            max_depth,
+            ancestors: Vec::new(),
+            out: ExpandedContext::default(),
+        }
+    }
+}
+
+/// Directed graph of memory files and the files they import.
+///
+/// Nodes are canonicalized paths; an edge `a -> b` means `a` imports `b`.
+#[derive(Debug, Clone, Default)]
+pub struct ImportGraph {
+    edges: BTreeMap<PathBuf, Vec<PathBuf>>,
+    import_lines: HashMap<(PathBuf, PathBuf), usize>,
+    /// Imports in imported files that the import policy refused.
+    denied: Vec<(PathBuf, Import)>,
+}
+
+impl ImportGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds the graph from the resolved imports of `files` without reading imported files.
+    pub fn from_files(files: &[MemoryFile]) -> Self {
+        let mut graph = Self::new();
+
+        for file in files {
+            let path = canonical_path(&file.path);
+            graph.add_node(&path);
+            for import in &file.imports {
+                for resolved in import.targets() {
+                    graph.add_edge(&path, resolved, import.line_number);
+                }
+            }
+        }
+
+        graph
+    }
+
+    pub fn add_node(&mut self, path: &Path) {
+        self.edges.entry(path.to_path_buf()).or_default();
+    }
+
+    pub fn add_edge(&mut self, from: &Path, to: &Path, line_number: usize) {
+        self.add_node(to);
+        self.import_lines
+            .entry((from.to_path_buf(), to.to_path_buf()))
+            .or_insert(line_number);
+        let deps = self.edges.entry(from.to_path_buf()).or_default();
+        if !deps.iter().any(|dep| dep == to) {
+            deps.push(to.to_path_buf());
+        }
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = &PathBuf> {
+        self.edges.keys()
+    }
+
+    pub fn len(&self) -> usize {
+        self.edges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.edges.is_empty()
+    }
+
+    pub fn contains(&self, path: &Path) -> bool {
+        self.edges.contains_key(path)
+    }
+
+    /// Files directly imported by `path`.
+    pub fn dependencies(&self, path: &Path) -> &[PathBuf] {
+        self.edges.get(path).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Imports of imported files with targets refused by the import policy,
+    /// with the importing file. Scanned files keep theirs on `Import`.
+    pub fn denied_imports(&self) -> &[(PathBuf, Import)] {
+        &self.denied
+    }
+
+    /// Line in `from` holding the first import of `to`.
+    pub fn import_line(&self, from: &Path, to: &Path) -> Option<usize> {
+        self.import_lines
+            .get(&(from.to_path_buf(), to.to_path_buf()))
+            .copied()
+    }
+
+    /// Files that directly import `path`.
+    pub fn dependents(&self, path: &Path) -> Vec<&PathBuf> {
+        self.edges
+            .iter()
+            .filter(|(_, deps)| deps.iter().any(|dep| dep == path))
+            .map(|(node, _)| node)
+            .collect()
+    }
+
+    /// Files that are not imported by any other file.
+    pub fn roots(&self) -> Vec<&PathBuf> {
+        let imported: BTreeSet<&PathBuf> = self.edges.values().flatten().collect();
+        self.nodes()
+            .filter(|node| !imported.contains(node))
+            .collect()
+    }
+
+    /// Files that neither import nor are imported by anything.
+    pub fn orphans(&self) -> Vec<&PathBuf> {
+        self.roots()
+            .into_iter()
+            .filter(|node| self.dependencies(node).is_empty())
+            .collect()
+    }
+
+    /// Orders nodes so that every file comes before the files it imports.
+    ///
+    /// Fails if the graph contains a cycle.
+    pub fn topological_order(&self) -> Result<Vec<PathBuf>> {
+        let mut in_degree: BTreeMap<&PathBuf, usize> = self.nodes().map(|n| (n, 0)).collect();
+        for dep in self.edges.values().flatten() {
+            *in_degree.entry(dep).or_default() += 1;
+        }
+
+        let mut ready: VecDeque<&PathBuf> = in_degree
+            .iter()
+            .filter(|(_, &degree)| degree == 0)
+            .map(|(&node, _)| node)
+            .collect();
+        let mut order = Vec::with_capacity(self.len());
+
+        while let Some(node) = ready.pop_front() {
+            order.push(node.clone());
+            for dep in self.dependencies(node) {
+                let degree = in_degree.get_mut(dep).unwrap();
+                *degree -= 1;
+                if *degree == 0 {
+                    ready.push_back(dep);
+                }
+            }
+        }
+
+        if order.len() != self.len() {
+            bail!("Import graph contains a cycle");
+        }
+
+        Ok(order)
+    }
+
+    /// Strongly connected components of the graph, in Tarjan's discovery order.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<PathBuf>> {
+        let mut state = TarjanState::default();
+        for node in self.nodes() {
+            if !state.index.contains_key(node) {
+                self.strong_connect(node, &mut state);
+            }
         }
+        state.components
+    }
+
+    fn strong_connect<'a>(&'a self, node: &'a PathBuf, state: &mut TarjanState<'a>) {
+        state.index.insert(node, state.next_index);
+        state.low_link.insert(node, state.next_index);
+        state.next_index += 1;
+        state.stack.push(node);
+        state.on_stack.insert(node);
 
-        if visited.contains(current) {
-            return None;
+        for dep in self.dependencies(node) {
+            if !state.index.contains_key(dep) {
+                self.strong_connect(dep, state);
+                let low = state.low_link[node].min(state.low_link[dep]);
+                state.low_link.insert(node, low);
+            } else if state.on_stack.contains(dep) {
+                let low = state.low_link[node].min(state.index[dep]);
+                state.low_link.insert(node, low);
+            }
         }
 
-        visited.insert(current.to_path_buf());
-        stack.push(current.to_path_buf());
+        if state.low_link[node] == state.index[node] {
+            let mut component = Vec::new();
+            while let Some(member) = state.stack.pop() {
+                state.on_stack.remove(member);
+                component.push(member.clone());
+                if member == node {
+                    break;
+                }
+            }
+            component.sort();
+            state.components.push(component);
+        }
+    }
+
+    /// One representative cycle per strongly connected component of the
+    /// graph; other cycles through the same files are not listed.
+    ///
+    /// Each cycle starts at the component's smallest path and follows the
+    /// shortest import chain back to it.
+    pub fn cycles(&self) -> Vec<CircularImport> {
+        let mut cycles: Vec<CircularImport> = self
+            .strongly_connected_components()
+            .into_iter()
+            .filter(|component| {
+                component.len() > 1 || self.dependencies(&component[0]).contains(&component[0])
+            })
+            .filter_map(|component| {
+                let members: HashSet<&PathBuf> = component.iter().collect();
+                self.shortest_cycle(&component[0], &members)
+            })
+            .map(|cycle| CircularImport { cycle })
+            .collect();
+
+        cycles.sort_by(|a, b| a.cycle.cmp(&b.cycle));
+        cycles
+    }
+
+    fn shortest_cycle(&self, start: &PathBuf, members: &HashSet<&PathBuf>) -> Option<Vec<PathBuf>> {
+        let mut previous: HashMap<&PathBuf, &PathBuf> = HashMap::new();
+        let mut queue = VecDeque::from([start]);
 
-        if let Some(deps) = graph.get(current) {
-            for dep in deps {
-                if let Some(cycle) = Self::find_cycle(dep, graph, visited, stack) {
+        while let Some(node) = queue.pop_front() {
+            for dep in self.dependencies(node) {
+                if dep == start {
+                    let mut cycle = vec![node.clone()];
+                    let mut current = node;
+                    while let Some(&prev) = previous.get(current) {
+                        cycle.push(prev.clone());
+                        current = prev;
+                    }
+                    cycle.reverse();
                     return Some(cycle);
                 }
+                if members.contains(dep) && !previous.contains_key(dep) {
+                    previous.insert(dep, node);
+                    queue.push_back(dep);
+                }
             }
         }
 
-        stack.pop();
         None
     }
 
-    pub fn expand_imports(&self, file: &MemoryFile, max_depth: usize) -> Result<String> {
-        self.expand_imports_recursive(file, 0, max_depth, &mut HashSet::new())
+    /// Length of the longest import chain (in hops) starting at any node.
+    ///
+    /// Edges that close a cycle are ignored.
+    pub fn max_depth(&self) -> usize {
+        let mut memo = HashMap::new();
+        self.nodes()
+            .map(|node| self.depth_from(node, &mut memo, &mut Vec::new()).0)
+            .max()
+            .unwrap_or(0)
     }
 
-    fn expand_imports_recursive(
-        &self,
-        file: &MemoryFile,
-        depth: usize,
-        max_depth: usize,
-        visited: &mut HashSet<PathBuf>,
-    ) -> Result<String> {
-        if depth >= max_depth {
-            return Ok(file.content.clone());
+    /// Longest import chain starting at `from`, including `from` itself, or
+    /// an empty chain if `from` is not in the graph.
+    ///
+    /// Edges that close a cycle are ignored.
+    pub fn longest_chain(&self, from: &Path) -> Vec<PathBuf> {
+        let Some((start, _)) = self.edges.get_key_value(from) else {
+            return Vec::new();
+        };
+
+        let mut memo = HashMap::new();
+        let mut chain = vec![start];
+        loop {
+            let mut next: Option<(&PathBuf, usize)> = None;
+            for dep in self.dependencies(chain[chain.len() - 1]) {
+                if chain.contains(&dep) {
+                    continue;
+                }
+                let (depth, _) = self.depth_from(dep, &mut memo, &mut chain.clone());
+                if next.is_none_or(|(_, best)| depth > best) {
+                    next = Some((dep, depth));
+                }
+            }
+            match next {
+                Some((dep, _)) => chain.push(dep),
+                None => break,
+            }
         }
 
-        if !visited.insert(file.path.clone()) {
-            bail!("Circular import detected: {}", file.path.display());
+        chain.into_iter().cloned().collect()
+    }
+
+    /// Longest chain below `node` that avoids the files on `stack`, and
+    /// whether that depth holds regardless of the stack. Only such depths are
+    /// memoized; inside a cycle the depth depends on the path taken.
+    fn depth_from<'a>(
+        &'a self,
+        node: &'a PathBuf,
+        memo: &mut HashMap<&'a PathBuf, usize>,
+        stack: &mut Vec<&'a PathBuf>,
+    ) -> (usize, bool) {
+        if let Some(&depth) = memo.get(node) {
+            return (depth, true);
         }
 
-        let mut expanded = file.content.clone();
+        stack.push(node);
+        let mut depth = 0;
+        let mut independent = true;
+        for dep in self.dependencies(node) {
+            if stack.contains(&dep) {
+                independent = false;
+                continue;
+            }
+            let (dep_depth, dep_independent) = self.depth_from(dep, memo, stack);
+            depth = depth.max(1 + dep_depth);
+            independent &= dep_independent;
+        }
+        stack.pop();
 
-        for import in &file.imports {
-            if let Some(resolved_path) = &import.resolved_path {
-                if let Ok(imported_content) = fs::read_to_string(resolved_path) {
-                    let import_line = format!("{{{{import {}}}}}", import.path);
-                    let replacement = format!(
-                        "<!-- Import from: {} -->\n{}\n<!-- End import -->",
-                        resolved_path.display(),
-                        imported_content
-                    );
-                    expanded = expanded.replace(&import_line, &replacement);
+        if independent {
+            memo.insert(node, depth);
+        }
+        (depth, independent)
+    }
+
+    /// Shortest import chain from `from` to every file it reaches, each
+    /// starting with `from` and ending with the reached file. `from` itself
+    /// maps to a chain of one.
+    pub fn shortest_chains(&self, from: &Path) -> BTreeMap<PathBuf, Vec<PathBuf>> {
+        let Some((start, _)) = self.edges.get_key_value(from) else {
+            return BTreeMap::new();
+        };
+
+        let mut previous: HashMap<&PathBuf, &PathBuf> = HashMap::new();
+        let mut order = vec![start];
+        let mut queue = VecDeque::from([start]);
+        while let Some(node) = queue.pop_front() {
+            for dep in self.dependencies(node) {
+                if dep != start && !previous.contains_key(dep) {
+                    previous.insert(dep, node);
+                    order.push(dep);
+                    queue.push_back(dep);
                 }
             }
         }
 
-        Ok(expanded)
+        order
+            .into_iter()
+            .map(|node| {
+                let mut chain = vec![node.clone()];
+                let mut current = node;
+                while let Some(&prev) = previous.get(current) {
+                    chain.push(prev.clone());
+                    current = prev;
+                }
+                chain.reverse();
+                (node.clone(), chain)
+            })
+            .collect()
+    }
+}
+
+#[derive(Default)]
+struct TarjanState<'a> {
+    next_index: usize,
+    index: HashMap<&'a PathBuf, usize>,
+    low_link: HashMap<&'a PathBuf, usize>,
+    stack: Vec<&'a PathBuf>,
+    on_stack: HashSet<&'a PathBuf>,
+    components: Vec<Vec<PathBuf>>,
+}
+
+/// Resolves an import selector to an inclusive line range of `path`.
+/// Unreadable and binary files match no lines, so the import is reported as
+/// invalid instead of failing the whole run.
+fn select_lines(path: &Path, selector: &ImportSelector) -> Result<Option<(usize, usize)>> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return Ok(None);
+    };
+    let line_count = content.lines().count();
+
+    Ok(match selector {
+        ImportSelector::Section(name) => Parser::new().parse_content(&content)?.section_range(name),
+        ImportSelector::Lines { start, end } => {
+            (*start >= 1 && start <= end && *start <= line_count)
+                .then(|| (*start, (*end).min(line_count)))
+        }
+    })
+}
+
+fn is_markdown(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("md" | "markdown" | "mdx")
+    )
+}
+
+fn import_label(target: &Path, import: &Import) -> String {
+    match &import.selector {
+        Some(ImportSelector::Section(name)) => format!("{}#{}", target.display(), name),
+        Some(ImportSelector::Lines { .. }) => match import.selected_lines {
+            Some((start, end)) => format!("{}:{}-{}", target.display(), start, end),
+            None => target.display().to_string(),
+        },
+        None => target.display().to_string(),
+    }
+}
+
+/// Emits a non-Markdown import inside a code fence tagged with its extension.
+fn push_fenced(
+    target: &Path,
+    content: &str,
+    lines: Option<(usize, usize)>,
+    importer: &Path,
+    import_line: usize,
+    expansion: &mut Expansion,
+) {
+    let (first, last) = lines.unwrap_or((1, usize::MAX));
+    let selected: Vec<(usize, &str)> = content
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| (idx + 1, line))
+        .filter(|(line_number, _)| *line_number >= first && *line_number <= last)
+        .collect();
+
+    // The fence must be longer than any backtick run inside the content
+    let longest_run = selected
+        .iter()
+        .map(|(_, line)| line.trim_start().chars().take_while(|&c| c == '`').count())
+        .max()
+        .unwrap_or(0);
+    let fence = "`".repeat(longest_run.max(2) + 1);
+    let language = target
+        .extension()
+        .map(|e| e.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    expansion
+        .out
+        .push_generated(&format!("{}{}", fence, language), importer, import_line);
+    for (line_number, line) in selected {
+        expansion.out.push_line(line, target, line_number);
     }
+    expansion.out.push_generated(&fence, importer, import_line);
+}
+
+/// Expands a leading `~/` to the user's home directory.
+pub(crate) fn expand_home(path: &str) -> String {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest).to_string_lossy().to_string(),
+        _ => path.to_string(),
+    }
+}
+
+fn canonical_path(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
 }
 
 #[derive(Debug, Clone)]
@@ -185,7 +888,7 @@ pub struct CircularImport {
 impl std::fmt::Display for CircularImport {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "Circular import: ")?;
-        for (i, path) in self.cycle.iter().enumerate() {
+        for (i, path) in self.cycle.iter().chain(self.cycle.first()).enumerate() {
             if i > 0 {
                 write!(f, " -> ")?;
             }
@@ -194,3 +897,280 @@ impl std::fmt::Display for CircularImport {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(edges: &[(&str, &str)]) -> ImportGraph {
+        let mut graph = ImportGraph::new();
+        for (line, (from, to)) in edges.iter().enumerate() {
+            graph.add_node(Path::new(from));
+            graph.add_edge(Path::new(from), Path::new(to), line + 1);
+        }
+        graph
+    }
+
+    fn paths(names: &[&str]) -> Vec<PathBuf> {
+        names.iter().map(PathBuf::from).collect()
+    }
+
+    #[test]
+    fn selectors_of_binary_files_match_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("logo.png"), [0x89, 0x50, 0xff, 0xfe]).unwrap();
+
+        let mut file = MemoryFile::from_content(
+            dir.path().join("CLAUDE.md"),
+            crate::core::MemoryType::ProjectMemory,
+            "@logo.png:1-2\n",
+        );
+        ImportResolver::new(dir.path())
+            .resolve_imports(&mut file)
+            .unwrap();
+        let import = &file.imports[0];
+        assert!(import.resolved_path.is_some());
+        assert_eq!(import.selected_lines, None);
+        assert!(!import.is_resolved());
+    }
+
+    fn resolve_with_aliases(root: &Path, aliases: &[(&str, &Path)], content: &str) -> Vec<Import> {
+        let mut config = Config::default();
+        for (name, dir) in aliases {
+            config
+                .imports
+                .aliases
+                .insert(name.to_string(), dir.to_string_lossy().to_string());
+        }
+        let mut file = MemoryFile::from_content(
+            root.join("CLAUDE.md"),
+            crate::core::MemoryType::ProjectMemory,
+            content,
+        );
+        ImportResolver::from_config(root, &config)
+            .resolve_imports(&mut file)
+            .unwrap();
+        file.imports
+    }
+
+    #[test]
+    fn aliases_match_whole_path_components() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("team/docs")).unwrap();
+        fs::create_dir_all(dir.path().join("shared-notes")).unwrap();
+        fs::write(dir.path().join("team/docs/style.md"), "Style\n").unwrap();
+        fs::write(dir.path().join("shared-notes/todo.md"), "Todo\n").unwrap();
+
+        let imports = resolve_with_aliases(
+            dir.path(),
+            &[("shared", Path::new("team/docs"))],
+            "@shared/style.md\n@shared/\n@shared-notes/todo.md\n@unknown/style.md\n",
+        );
+        let style = dir
+            .path()
+            .join("team/docs/style.md")
+            .canonicalize()
+            .unwrap();
+
+        assert_eq!(imports[0].alias.as_deref(), Some("shared"));
+        assert_eq!(imports[0].resolved_path.as_ref(), Some(&style));
+        assert_eq!(imports[1].alias.as_deref(), Some("shared"));
+        assert_eq!(imports[1].resolved_paths, vec![style]);
+
+        // A longer first component is a plain path, not the alias
+        assert_eq!(imports[2].alias, None);
+        assert!(imports[2].resolved_path.is_some());
+
+        assert_eq!(imports[3].alias, None);
+        assert!(!imports[3].is_resolved());
+        assert!(imports[3].policy_violations.is_empty());
+    }
+
+    #[test]
+    fn alias_targets_outside_the_project_follow_the_policy() {
+        let dir = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        fs::write(outside.path().join("style.md"), "Style\n").unwrap();
+        fs::write(outside.path().join(".env"), "TOKEN=1\n").unwrap();
+        let outside_style = outside.path().join("style.md");
+
+        let imports = resolve_with_aliases(
+            dir.path(),
+            &[("ext", outside.path())],
+            "@ext/style.md\n@ext/.env\n",
+        );
+
+        // The alias directory is an allowed root
+        assert_eq!(imports[0].alias.as_deref(), Some("ext"));
+        assert_eq!(
+            imports[0].resolved_path.as_ref(),
+            Some(&outside_style.canonicalize().unwrap())
+        );
+        assert!(imports[0].policy_violations.is_empty());
+
+        // Denied patterns still apply inside it
+        assert_eq!(imports[1].resolved_path, None);
+        assert_eq!(imports[1].policy_violations.len(), 1);
+        assert!(imports[1].policy_violations[0]
+            .reason
+            .starts_with("matches denied pattern"));
+
+        // Without the alias the same file is outside the allowed roots
+        let imports =
+            resolve_with_aliases(dir.path(), &[], &format!("@{}\n", outside_style.display()));
+        assert_eq!(imports[0].resolved_path, None);
+        assert_eq!(
+            imports[0].policy_violations[0].reason,
+            "outside allowed roots"
+        );
+    }
+
+    #[test]
+    fn hash_in_file_name_is_not_a_section() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("notes")).unwrap();
+        fs::write(dir.path().join("notes/#todo.md"), "# Open\nShip it\n").unwrap();
+
+        let mut file = MemoryFile::from_content(
+            dir.path().join("CLAUDE.md"),
+            crate::core::MemoryType::ProjectMemory,
+            "@notes/#todo.md\n@notes/#todo.md#open\n",
+        );
+        ImportResolver::new(dir.path())
+            .resolve_imports(&mut file)
+            .unwrap();
+        let target = dir.path().join("notes/#todo.md").canonicalize().unwrap();
+
+        let whole = &file.imports[0];
+        assert_eq!(whole.selector, None);
+        assert_eq!(whole.resolved_path.as_ref(), Some(&target));
+        assert!(whole.is_resolved());
+
+        let section = &file.imports[1];
+        assert_eq!(section.file_path(), "notes/#todo.md");
+        assert_eq!(section.resolved_path.as_ref(), Some(&target));
+        assert_eq!(section.selected_lines, Some((1, 2)));
+    }
+
+    #[test]
+    fn expansion_marks_circular_imports() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("CLAUDE.md"), "# Project\n@notes.md\n").unwrap();
+        fs::write(dir.path().join("notes.md"), "Notes\n@CLAUDE.md\n").unwrap();
+
+        let mut file = MemoryFile::from_content(
+            dir.path().join("CLAUDE.md"),
+            crate::core::MemoryType::ProjectMemory,
+            "# Project\n@notes.md\n",
+        );
+        let mut resolver = ImportResolver::new(dir.path());
+        resolver.resolve_imports(&mut file).unwrap();
+        let context = resolver.expand(&file, MAX_IMPORT_DEPTH).unwrap();
+
+        let lines: Vec<&str> = context.content.lines().collect();
+        assert_eq!(lines.len(), 7);
+        assert!(lines[2].starts_with("<!-- Import from: ") && lines[2].ends_with("notes.md -->"));
+        assert_eq!(lines[3..5], ["Notes", "@CLAUDE.md"]);
+        assert!(
+            lines[5].starts_with("<!-- Circular import: ") && lines[5].ends_with("CLAUDE.md -->")
+        );
+        assert_eq!(lines[6], "<!-- End import -->");
+    }
+
+    #[test]
+    fn depth_does_not_depend_on_visit_order_inside_cycles() {
+        // `c` is visited first from `b`, where `b` is on the stack
+        let graph = graph(&[("b", "c"), ("c", "b"), ("b", "d"), ("e", "c")]);
+        assert_eq!(graph.max_depth(), 3);
+        assert_eq!(
+            graph.longest_chain(Path::new("e")),
+            paths(&["e", "c", "b", "d"])
+        );
+    }
+
+    #[test]
+    fn shortest_chains_follow_the_fewest_hops() {
+        let graph = graph(&[("a", "b"), ("b", "c"), ("c", "d"), ("a", "d"), ("d", "a")]);
+        let chains = graph.shortest_chains(Path::new("a"));
+        assert_eq!(chains.len(), 4);
+        assert_eq!(chains[Path::new("a")], paths(&["a"]));
+        assert_eq!(chains[Path::new("d")], paths(&["a", "d"]));
+        assert_eq!(chains[Path::new("c")], paths(&["a", "b", "c"]));
+        assert!(graph.shortest_chains(Path::new("missing")).is_empty());
+    }
+
+    #[test]
+    fn cycles_start_at_the_smallest_path_of_each_component() {
+        let graph = graph(&[
+            ("c", "a"),
+            ("a", "b"),
+            ("b", "c"),
+            ("b", "a"),
+            ("d", "d"),
+            ("e", "a"),
+        ]);
+        let cycles: Vec<Vec<PathBuf>> = graph.cycles().into_iter().map(|c| c.cycle).collect();
+        // `a -> b -> a` is shorter than `a -> b -> c -> a`
+        assert_eq!(cycles, vec![paths(&["a", "b"]), paths(&["d"])]);
+        assert!(graph.topological_order().is_err());
+    }
+
+    #[test]
+    fn topological_order_puts_importers_first() {
+        let graph = graph(&[("a", "c"), ("b", "c"), ("c", "d"), ("a", "d")]);
+        assert!(graph.cycles().is_empty());
+        let order = graph.topological_order().unwrap();
+        let position = |name: &str| order.iter().position(|p| p == Path::new(name)).unwrap();
+        assert_eq!(order.len(), 4);
+        assert!(position("a") < position("c") && position("b") < position("c"));
+        assert!(position("c") < position("d"));
+    }
+
+    #[test]
+    fn longest_chain_takes_the_most_hops() {
+        let graph = graph(&[("a", "d"), ("a", "b"), ("b", "c"), ("c", "d"), ("d", "a")]);
+        assert_eq!(
+            graph.longest_chain(Path::new("a")),
+            paths(&["a", "b", "c", "d"])
+        );
+        assert_eq!(
+            graph.longest_chain(Path::new("d")),
+            paths(&["d", "a", "b", "c"])
+        );
+        assert!(graph.longest_chain(Path::new("missing")).is_empty());
+    }
+
+    #[test]
+    fn selectors_pick_sections_and_line_ranges() {
+        let dir = tempfile::tempdir().unwrap();
+        let guide = dir.path().join("guide.md");
+        fs::write(
+            &guide,
+            "# Guide\nIntro\n## Testing\nRun tests\n### Unit\nFast\n## Style\nTabs\n",
+        )
+        .unwrap();
+        let select = |selector: ImportSelector| select_lines(&guide, &selector).unwrap();
+
+        assert_eq!(
+            select(ImportSelector::Section("Testing".into())),
+            Some((3, 6))
+        );
+        assert_eq!(
+            select(ImportSelector::Section("style".into())),
+            Some((7, 8))
+        );
+        assert_eq!(select(ImportSelector::Section("Missing".into())), None);
+        assert_eq!(
+            select(ImportSelector::Lines { start: 2, end: 4 }),
+            Some((2, 4))
+        );
+        // Ranges past the end are clamped, but must start inside the file
+        assert_eq!(
+            select(ImportSelector::Lines { start: 7, end: 20 }),
+            Some((7, 8))
+        );
+        assert_eq!(select(ImportSelector::Lines { start: 9, end: 9 }), None);
+        assert_eq!(select(ImportSelector::Lines { start: 4, end: 2 }), None);
+        assert_eq!(select(ImportSelector::Lines { start: 0, end: 2 }), None);
+    }
+}
@@ -1,17 +1,27 @@
+use super::cache::ShardedCache;
 use super::{FileMetadata, MemoryFile, MemoryType};
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
 use rayon::prelude::*;
+use regex::Regex;
 use sha2::{Digest, Sha256};
 use std::fs;
 use std::io::Read;
 use std::path::Path;
+use std::sync::Arc;
 use walkdir::WalkDir;
 
+static AT_IMPORT_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?:^|\s)@(\S+)").unwrap());
+
+static INLINE_CODE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"`[^`]*`").unwrap());
+
 pub struct Scanner {
     include_subdirs: bool,
     max_depth: Option<usize>,
     follow_symlinks: bool,
+    parallel: bool,
+    cache: Option<Arc<ShardedCache<MemoryFile>>>,
 }
 
 impl Scanner {
@@ -20,6 +30,8 @@ impl Scanner {
             include_subdirs: false,
             max_depth: None,
             follow_symlinks: false,
+            parallel: true,
+            cache: None,
         }
     }
 
@@ -38,6 +50,16 @@ impl Scanner {
         self
     }
 
+    pub fn with_parallel(mut self, parallel: bool) -> Self {
+        self.parallel = parallel;
+        self
+    }
+
+    pub fn with_cache(mut self, cache: Arc<ShardedCache<MemoryFile>>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
     pub fn scan<P: AsRef<Path>>(&self, path: P) -> Result<Vec<MemoryFile>> {
         let path = path.as_ref();
         let mut files = Vec::new();
@@ -95,15 +117,33 @@ impl Scanner {
             })
             .collect();
 
-        let files: Vec<_> = entries
-            .par_iter()
-            .filter_map(|entry| self.scan_file(entry.path(), MemoryType::SubdirMemory).ok())
-            .collect();
+        let scan =
+            |entry: &walkdir::DirEntry| self.scan_file(entry.path(), MemoryType::SubdirMemory).ok();
+        let files: Vec<_> = if self.parallel {
+            entries.par_iter().filter_map(scan).collect()
+        } else {
+            entries.iter().filter_map(scan).collect()
+        };
 
         Ok(files)
     }
 
     fn scan_file(&self, path: &Path, file_type: MemoryType) -> Result<MemoryFile> {
+        match &self.cache {
+            Some(cache) => {
+                let file =
+                    cache.get_or_insert_with(path, || self.read_file(path, file_type.clone()))?;
+                // The same file is a project memory from its own directory and a
+                // subdirectory memory from above, so the type is not cached
+                let mut file = MemoryFile::clone(&file);
+                file.file_type = file_type;
+                Ok(file)
+            }
+            None => self.read_file(path, file_type),
+        }
+    }
+
+    fn read_file(&self, path: &Path, file_type: MemoryType) -> Result<MemoryFile> {
         let content = fs::read_to_string(path)
             .with_context(|| format!("Failed to read file: {}", path.display()))?;
 
@@ -154,37 +194,63 @@ impl Scanner {
     }
 
     fn extract_imports(&self, content: &str) -> Vec<super::Import> {
-        let mut imports = Vec::new();
-
-        for (line_number, line) in content.lines().enumerate() {
-            if line.trim_start().starts_with("{{import") {
-                if let Some(path) = self.parse_import_line(line) {
-                    imports.push(super::Import {
-                        path,
-                        line_number: line_number + 1,
-                        resolved_path: None,
-                    });
-                }
-            }
-        }
+        extract_imports(content)
+    }
+}
 
-        imports
+impl Default for Scanner {
+    fn default() -> Self {
+        Self::new()
     }
+}
 
-    fn parse_import_line(&self, line: &str) -> Option<String> {
-        // Basic import parsing - will be enhanced
-        let trimmed = line.trim();
-        if trimmed.starts_with("{{import") && trimmed.ends_with("}}") {
-            let content = &trimmed[8..trimmed.len() - 2].trim();
-            Some(content.to_string())
-        } else {
-            None
+pub(crate) fn extract_imports(content: &str) -> Vec<super::Import> {
+    let mut imports = Vec::new();
+    let mut in_code_block = false;
+
+    for (line_number, line) in content.lines().enumerate() {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+
+        if line.trim_start().starts_with("{{import") {
+            if let Some(path) = parse_import_line(line) {
+                imports.push(super::Import::new(path, line_number + 1));
+            }
+            continue;
+        }
+
+        for path in parse_at_imports(line) {
+            imports.push(super::Import::new(path, line_number + 1));
         }
     }
+
+    imports
 }
 
-impl Default for Scanner {
-    fn default() -> Self {
-        Self::new()
+fn parse_import_line(line: &str) -> Option<String> {
+    // Basic import parsing - will be enhanced
+    let trimmed = line.trim();
+    if trimmed.starts_with("{{import") && trimmed.ends_with("}}") {
+        let content = &trimmed[8..trimmed.len() - 2].trim();
+        Some(content.to_string())
+    } else {
+        None
     }
 }
+
+/// Extracts Claude Code style `@path` imports, ignoring inline code spans.
+fn parse_at_imports(line: &str) -> Vec<String> {
+    AT_IMPORT_REGEX
+        .captures_iter(&INLINE_CODE_REGEX.replace_all(line, ""))
+        .filter_map(|captures| {
+            let path = captures[1].trim_end_matches(['.', ',', ';', ':', ')']);
+            // Require something path-like so `@username` mentions are not imports
+            path.contains(['/', '.', '*']).then(|| path.to_string())
+        })
+        .collect()
+}
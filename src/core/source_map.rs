@@ -0,0 +1,77 @@
+use super::MemoryFile;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Flattened context produced by import expansion, with the origin of every line.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExpandedContext {
+    pub content: String,
+    pub source_map: SourceMap,
+}
+
+/// Maps each line of expanded output (1-based) to the file and line it came from.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SourceMap {
+    pub mappings: Vec<SourceLocation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SourceLocation {
+    pub file: PathBuf,
+    pub line: usize,
+    /// Set for lines ccat inserted itself (import markers, file headers); they
+    /// point at the line that caused them.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub generated: bool,
+}
+
+impl ExpandedContext {
+    /// The lines of `file` itself, without following its imports.
+    pub fn unexpanded(file: &MemoryFile) -> Self {
+        let mut context = Self::default();
+        for (idx, line) in file.content.lines().enumerate() {
+            context.push_line(line, &file.path, idx + 1);
+        }
+        context
+    }
+
+    pub fn push_line(&mut self, text: &str, file: &Path, line: usize) {
+        self.push(text, file, line, false);
+    }
+
+    pub fn push_generated(&mut self, text: &str, file: &Path, line: usize) {
+        self.push(text, file, line, true);
+    }
+
+    fn push(&mut self, text: &str, file: &Path, line: usize, generated: bool) {
+        self.content.push_str(text);
+        self.content.push('\n');
+        self.source_map.mappings.push(SourceLocation {
+            file: file.to_path_buf(),
+            line,
+            generated,
+        });
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.source_map.mappings.len()
+    }
+
+    pub fn locate(&self, expanded_line: usize) -> Option<&SourceLocation> {
+        self.source_map.lookup(expanded_line)
+    }
+}
+
+impl SourceMap {
+    pub fn lookup(&self, expanded_line: usize) -> Option<&SourceLocation> {
+        expanded_line
+            .checked_sub(1)
+            .and_then(|idx| self.mappings.get(idx))
+    }
+}
+
+impl std::fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.file.display(), self.line)
+    }
+}
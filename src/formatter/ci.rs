@@ -0,0 +1,408 @@
+use super::plural;
+use super::report::ReportFormatter;
+use crate::analyzer::{Diagnostic, DiagnosticLevel, DiagnosticResult};
+use anyhow::Result;
+use std::collections::BTreeMap;
+
+// Name used for diagnostics that are not tied to a file
+const PROJECT: &str = "(project)";
+
+/// JUnit XML with one test suite per file and one test case per rule that
+/// reported something in that file. Errors and warnings fail their test
+/// case; infos are attached as output of a passing one.
+pub struct JunitReport;
+
+impl Default for JunitReport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JunitReport {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn testcase(&self, file: &str, rule: &str, diagnostics: &[&Diagnostic]) -> String {
+        let details: String = diagnostics
+            .iter()
+            .map(|d| format!("{}: {}\n", location(d), d.message))
+            .collect();
+        let worst = diagnostics
+            .iter()
+            .map(|d| d.level)
+            .max_by_key(|level| level.rank())
+            .unwrap_or(DiagnosticLevel::Info);
+
+        let body = if worst == DiagnosticLevel::Info {
+            format!("      <system-out>{}</system-out>\n", escape_xml(&details))
+        } else {
+            format!(
+                "      <failure message=\"{}\" type=\"{}\">{}</failure>\n",
+                escape_xml(&diagnostics[0].message),
+                worst,
+                escape_xml(&details)
+            )
+        };
+
+        format!(
+            "    <testcase classname=\"{}\" name=\"{}\">\n{}    </testcase>\n",
+            escape_xml(file),
+            escape_xml(rule),
+            body
+        )
+    }
+}
+
+impl ReportFormatter for JunitReport {
+    fn render(&self, result: &DiagnosticResult) -> Result<String> {
+        let mut suites = String::new();
+        let mut tests = 0;
+        let mut failures = 0;
+
+        for (file, rules) in by_file_and_rule(result) {
+            let failed = rules
+                .values()
+                .filter(|ds| ds.iter().any(|d| d.level != DiagnosticLevel::Info))
+                .count();
+            tests += rules.len();
+            failures += failed;
+
+            suites.push_str(&format!(
+                "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\">\n",
+                escape_xml(file),
+                rules.len(),
+                failed
+            ));
+            for (rule, diagnostics) in &rules {
+                suites.push_str(&self.testcase(file, rule, diagnostics));
+            }
+            suites.push_str("  </testsuite>\n");
+        }
+
+        // A clean run still reports one passing test so CI shows the check ran
+        if tests == 0 {
+            tests = 1;
+            suites.push_str(
+                "  <testsuite name=\"ccat\" tests=\"1\" failures=\"0\">\n    <testcase classname=\"ccat\" name=\"diagnose\"/>\n  </testsuite>\n",
+            );
+        }
+
+        Ok(format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites name=\"ccat\" tests=\"{}\" failures=\"{}\">\n{}</testsuites>\n",
+            tests, failures, suites
+        ))
+    }
+}
+
+/// Checkstyle XML, understood by most code review and CI annotation tools.
+pub struct CheckstyleReport;
+
+impl Default for CheckstyleReport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CheckstyleReport {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl ReportFormatter for CheckstyleReport {
+    fn render(&self, result: &DiagnosticResult) -> Result<String> {
+        let mut output = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<checkstyle version=\"4.3\">\n",
+        );
+
+        for (file, rules) in by_file_and_rule(result) {
+            output.push_str(&format!("  <file name=\"{}\">\n", escape_xml(file)));
+            let mut diagnostics: Vec<&Diagnostic> = rules.into_values().flatten().collect();
+            diagnostics.sort_by_key(|d| (d.line, d.column));
+
+            for diagnostic in diagnostics {
+                let mut attributes = String::new();
+                if let Some(line) = diagnostic.line {
+                    attributes.push_str(&format!(" line=\"{}\"", line));
+                }
+                if let Some(column) = diagnostic.column {
+                    attributes.push_str(&format!(" column=\"{}\"", column));
+                }
+                output.push_str(&format!(
+                    "    <error{} severity=\"{}\" message=\"{}\" source=\"ccat.{}\"/>\n",
+                    attributes,
+                    diagnostic.level,
+                    escape_xml(&diagnostic.message),
+                    escape_xml(&diagnostic.code)
+                ));
+            }
+            output.push_str("  </file>\n");
+        }
+
+        output.push_str("</checkstyle>\n");
+        Ok(output)
+    }
+}
+
+/// GitHub Actions workflow commands, which turn each finding into an
+/// annotation on the pull request.
+pub struct GithubReport;
+
+impl Default for GithubReport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GithubReport {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl ReportFormatter for GithubReport {
+    fn render(&self, result: &DiagnosticResult) -> Result<String> {
+        let mut output = String::new();
+
+        for diagnostic in result.diagnostics() {
+            let command = match diagnostic.level {
+                DiagnosticLevel::Error => "error",
+                DiagnosticLevel::Warning => "warning",
+                DiagnosticLevel::Info => "notice",
+            };
+
+            let mut properties = Vec::new();
+            if let Some(file) = &diagnostic.file {
+                let file = file.strip_prefix("./").unwrap_or(file);
+                properties.push(format!("file={}", escape_property(file)));
+            }
+            if let Some(line) = diagnostic.line {
+                properties.push(format!("line={}", line));
+            }
+            if let Some(column) = diagnostic.column {
+                properties.push(format!("col={}", column));
+            }
+            properties.push(format!(
+                "title={}",
+                escape_property(&format!("ccat {}", diagnostic.code))
+            ));
+
+            output.push_str(&format!(
+                "::{} {}::{}\n",
+                command,
+                properties.join(","),
+                escape_data(&diagnostic.message)
+            ));
+        }
+
+        Ok(output)
+    }
+}
+
+/// Markdown summary for `$GITHUB_STEP_SUMMARY` or a pull request comment.
+pub struct MarkdownReport;
+
+impl Default for MarkdownReport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MarkdownReport {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl ReportFormatter for MarkdownReport {
+    fn render(&self, result: &DiagnosticResult) -> Result<String> {
+        let mut output = String::from("## ccat diagnostics\n\n");
+
+        let (errors, warnings, infos, files) = (
+            result.errors.len(),
+            result.warnings.len(),
+            result.infos.len(),
+            result.metrics.total_files,
+        );
+        output.push_str(&format!(
+            "**{}** {}, **{}** {}, **{}** {} in {} {}\n",
+            errors,
+            plural(errors, "error", "errors"),
+            warnings,
+            plural(warnings, "warning", "warnings"),
+            infos,
+            plural(infos, "info", "infos"),
+            files,
+            plural(files, "file", "files")
+        ));
+        if let Some(baseline) = &result.baseline {
+            output.push_str(&format!(
+                "\n{} known {} hidden by the baseline, {} baseline {} fixed\n",
+                baseline.suppressed,
+                plural(baseline.suppressed, "issue", "issues"),
+                baseline.fixed.len(),
+                plural(baseline.fixed.len(), "entry", "entries")
+            ));
+        }
+
+        if result.diagnostics().next().is_none() {
+            output.push_str("\nNo issues found.\n");
+            return Ok(output);
+        }
+
+        output.push_str("\n| Level | Rule | Location | Message |\n|---|---|---|---|\n");
+        for diagnostic in result.diagnostics() {
+            let icon = match diagnostic.level {
+                DiagnosticLevel::Error => "❌",
+                DiagnosticLevel::Warning => "⚠️",
+                DiagnosticLevel::Info => "ℹ️",
+            };
+            let fixable = if diagnostic.fix.is_some() {
+                " _(fixable)_"
+            } else {
+                ""
+            };
+            output.push_str(&format!(
+                "| {} {} | `{}` | `{}` | {}{} |\n",
+                icon,
+                diagnostic.level,
+                diagnostic.code,
+                escape_table(&location(diagnostic)),
+                escape_table(&diagnostic.message),
+                fixable
+            ));
+        }
+
+        Ok(output)
+    }
+}
+
+/// Groups diagnostics by file and rule, both sorted.
+fn by_file_and_rule(result: &DiagnosticResult) -> BTreeMap<&str, BTreeMap<&str, Vec<&Diagnostic>>> {
+    let mut groups: BTreeMap<&str, BTreeMap<&str, Vec<&Diagnostic>>> = BTreeMap::new();
+    for diagnostic in result.diagnostics() {
+        groups
+            .entry(diagnostic.file.as_deref().unwrap_or(PROJECT))
+            .or_default()
+            .entry(diagnostic.code.as_str())
+            .or_default()
+            .push(diagnostic);
+    }
+    groups
+}
+
+fn location(diagnostic: &Diagnostic) -> String {
+    let file = diagnostic.file.as_deref().unwrap_or(PROJECT);
+    match (diagnostic.line, diagnostic.column) {
+        (Some(line), Some(column)) => format!("{}:{}:{}", file, line, column),
+        (Some(line), None) => format!("{}:{}", file, line),
+        _ => file.to_string(),
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn escape_data(text: &str) -> String {
+    text.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_property(text: &str) -> String {
+    escape_data(text).replace(':', "%3A").replace(',', "%2C")
+}
+
+fn escape_table(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::PerformanceMetrics;
+
+    fn result(diagnostics: Vec<Diagnostic>) -> DiagnosticResult {
+        let mut result = DiagnosticResult {
+            errors: Vec::new(),
+            warnings: Vec::new(),
+            infos: Vec::new(),
+            suggestions: Vec::new(),
+            metrics: PerformanceMetrics::default(),
+            baseline: None,
+        };
+        for diagnostic in diagnostics {
+            result.push(diagnostic);
+        }
+        result
+    }
+
+    #[test]
+    fn escapes_special_characters() {
+        let message = "Use <a href=\"x\">&</a> at 100%\nnext line";
+
+        assert_eq!(
+            escape_xml(message),
+            "Use &lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt; at 100%\nnext line"
+        );
+        assert_eq!(escape_xml("it's"), "it&apos;s");
+        assert_eq!(
+            escape_data(message),
+            "Use <a href=\"x\">&</a> at 100%25%0Anext line"
+        );
+        assert_eq!(escape_data("a\r\nb"), "a%0D%0Ab");
+        assert_eq!(
+            escape_property("docs/a:b,c.md 50%"),
+            "docs/a%3Ab%2Cc.md 50%25"
+        );
+        assert_eq!(escape_table("a | b\nc"), "a \\| b c");
+    }
+
+    #[test]
+    fn reports_escape_messages_and_paths() {
+        let result = result(vec![Diagnostic::new(
+            DiagnosticLevel::Warning,
+            "rule",
+            "Use <tabs> & \"spaces\" | 50%\nnot both",
+        )
+        .with_file("./docs/a:b,c.md")
+        .with_line(2)]);
+
+        let github = GithubReport::new().render(&result).unwrap();
+        assert_eq!(
+            github,
+            "::warning file=docs/a%3Ab%2Cc.md,line=2,title=ccat rule::\
+             Use <tabs> & \"spaces\" | 50%25%0Anot both\n"
+        );
+
+        let junit = JunitReport::new().render(&result).unwrap();
+        assert!(junit.contains(
+            "<failure message=\"Use &lt;tabs&gt; &amp; &quot;spaces&quot; | 50%\nnot both\" \
+             type=\"warning\">"
+        ));
+
+        let markdown = MarkdownReport::new().render(&result).unwrap();
+        assert!(markdown
+            .contains("| `./docs/a:b,c.md:2` | Use <tabs> & \"spaces\" \\| 50% not both |\n"));
+    }
+
+    #[test]
+    fn clean_junit_run_has_a_passing_placeholder() {
+        let junit = JunitReport::new().render(&result(Vec::new())).unwrap();
+        assert_eq!(
+            junit,
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <testsuites name=\"ccat\" tests=\"1\" failures=\"0\">\n  \
+             <testsuite name=\"ccat\" tests=\"1\" failures=\"0\">\n    \
+             <testcase classname=\"ccat\" name=\"diagnose\"/>\n  \
+             </testsuite>\n\
+             </testsuites>\n"
+        );
+    }
+}
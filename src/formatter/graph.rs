@@ -0,0 +1,140 @@
+use crate::core::ImportGraph;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use termtree::Tree;
+
+pub struct GraphFormatter {
+    base_path: Option<PathBuf>,
+}
+
+impl Default for GraphFormatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GraphFormatter {
+    pub fn new() -> Self {
+        Self { base_path: None }
+    }
+
+    /// Show node labels relative to `path` when possible.
+    pub fn with_base_path<P: AsRef<Path>>(mut self, path: P) -> Self {
+        let path = path.as_ref();
+        self.base_path = Some(path.canonicalize().unwrap_or_else(|_| path.to_path_buf()));
+        self
+    }
+
+    pub fn to_dot(&self, graph: &ImportGraph) -> String {
+        let mut output =
+            String::from("digraph imports {\n    rankdir=LR;\n    node [shape=box];\n");
+
+        for node in graph.nodes() {
+            let deps = graph.dependencies(node);
+            if deps.is_empty() {
+                output.push_str(&format!("    \"{}\";\n", escape_dot(&self.label(node))));
+            }
+            for dep in deps {
+                output.push_str(&format!(
+                    "    \"{}\" -> \"{}\";\n",
+                    escape_dot(&self.label(node)),
+                    escape_dot(&self.label(dep))
+                ));
+            }
+        }
+
+        output.push_str("}\n");
+        output
+    }
+
+    pub fn to_mermaid(&self, graph: &ImportGraph) -> String {
+        let ids: BTreeMap<&PathBuf, String> = graph
+            .nodes()
+            .enumerate()
+            .map(|(i, node)| (node, format!("n{}", i)))
+            .collect();
+
+        let mut output = String::from("graph LR\n");
+        for (node, id) in &ids {
+            output.push_str(&format!(
+                "    {}[\"{}\"]\n",
+                id,
+                self.label(node).replace('"', "#quot;")
+            ));
+        }
+        for (node, id) in &ids {
+            for dep in graph.dependencies(node) {
+                output.push_str(&format!("    {} --> {}\n", id, ids[dep]));
+            }
+        }
+
+        output
+    }
+
+    pub fn to_tree(&self, graph: &ImportGraph) -> String {
+        let mut root = Tree::new("Import Graph".to_string());
+
+        for node in graph.roots() {
+            root.push(self.tree_node(graph, node, &mut HashSet::new()));
+        }
+
+        // Nodes only reachable through a cycle have no root; list them separately
+        let mut reachable = HashSet::new();
+        for node in graph.roots() {
+            collect_reachable(graph, node, &mut reachable);
+        }
+        for node in graph.nodes() {
+            if !reachable.contains(node) {
+                root.push(self.tree_node(graph, node, &mut HashSet::new()));
+                collect_reachable(graph, node, &mut reachable);
+            }
+        }
+
+        format!("{}", root)
+    }
+
+    fn tree_node<'a>(
+        &self,
+        graph: &'a ImportGraph,
+        node: &'a PathBuf,
+        ancestors: &mut HashSet<&'a PathBuf>,
+    ) -> Tree<String> {
+        if ancestors.contains(node) {
+            return Tree::new(format!("{} (cycle)", self.label(node)));
+        }
+
+        ancestors.insert(node);
+        let mut tree = Tree::new(self.label(node));
+        for dep in graph.dependencies(node) {
+            tree.push(self.tree_node(graph, dep, ancestors));
+        }
+        ancestors.remove(node);
+
+        tree
+    }
+
+    fn label(&self, path: &Path) -> String {
+        self.base_path
+            .as_ref()
+            .and_then(|base| path.strip_prefix(base).ok())
+            .unwrap_or(path)
+            .display()
+            .to_string()
+    }
+}
+
+fn collect_reachable<'a>(
+    graph: &'a ImportGraph,
+    node: &'a PathBuf,
+    seen: &mut HashSet<&'a PathBuf>,
+) {
+    if seen.insert(node) {
+        for dep in graph.dependencies(node) {
+            collect_reachable(graph, dep, seen);
+        }
+    }
+}
+
+fn escape_dot(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
@@ -1,5 +1,9 @@
+pub mod ci;
 pub mod diff;
+pub mod graph;
 pub mod json;
+pub mod report;
+pub mod sarif;
 pub mod text;
 pub mod tree;
 
@@ -10,7 +14,20 @@ pub trait Formatter {
     fn format(&self, files: &[MemoryFile]) -> Result<()>;
 }
 
+/// `singular` for a count of one, `plural` otherwise.
+pub fn plural<'a>(count: usize, singular: &'a str, plural: &'a str) -> &'a str {
+    if count == 1 {
+        singular
+    } else {
+        plural
+    }
+}
+
+pub use ci::{CheckstyleReport, GithubReport, JunitReport, MarkdownReport};
 pub use diff::DiffFormatter;
+pub use graph::GraphFormatter;
 pub use json::JsonFormatter;
+pub use report::{JsonReport, ReportFormatter, TextReport};
+pub use sarif::SarifReport;
 pub use text::TextFormatter;
 pub use tree::TreeFormatter;
@@ -0,0 +1,216 @@
+use super::plural;
+use super::text::format_size;
+use crate::analyzer::{Diagnostic, DiagnosticLevel, DiagnosticResult};
+use anyhow::Result;
+use colored::Colorize;
+
+/// Renders a `DiagnosticResult` for output, e.g. by `ccat diagnose`.
+pub trait ReportFormatter {
+    fn render(&self, result: &DiagnosticResult) -> Result<String>;
+}
+
+pub struct TextReport;
+
+impl Default for TextReport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TextReport {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn format_diagnostic(&self, diagnostic: &Diagnostic) -> String {
+        let level = match diagnostic.level {
+            DiagnosticLevel::Error => "error".red().bold(),
+            DiagnosticLevel::Warning => "warning".yellow().bold(),
+            DiagnosticLevel::Info => "info".blue().bold(),
+        };
+        let location = match (&diagnostic.file, diagnostic.line, diagnostic.column) {
+            (Some(file), Some(line), Some(column)) => format!(" {}:{}:{}", file, line, column),
+            (Some(file), Some(line), None) => format!(" {}:{}", file, line),
+            (Some(file), None, _) => format!(" {}", file),
+            _ => String::new(),
+        };
+
+        let fixable = match diagnostic.fix {
+            Some(_) => format!(" {}", "(fixable)".dimmed()),
+            None => String::new(),
+        };
+
+        format!(
+            "{}[{}]{}: {}{}",
+            level,
+            diagnostic.code,
+            location.bold(),
+            diagnostic.message,
+            fixable
+        )
+    }
+}
+
+impl ReportFormatter for TextReport {
+    fn render(&self, result: &DiagnosticResult) -> Result<String> {
+        let mut output = String::new();
+
+        for diagnostic in result.diagnostics() {
+            output.push_str(&self.format_diagnostic(diagnostic));
+            output.push('\n');
+        }
+        for suggestion in &result.suggestions {
+            output.push_str(&format!(
+                "{}: {}\n",
+                "suggestion".green(),
+                suggestion.message
+            ));
+        }
+        if !output.is_empty() {
+            output.push('\n');
+        }
+
+        let metrics = &result.metrics;
+        output.push_str(&format!(
+            "{}\n",
+            format!(
+                "Files: {} | Size: {} | Import depth: {} | Circular imports: {}",
+                metrics.total_files,
+                format_size(metrics.total_size),
+                metrics.import_depth,
+                metrics.circular_imports
+            )
+            .dimmed()
+        ));
+        for chain in &metrics.import_chains {
+            let names: Vec<String> = chain.iter().map(|p| p.display().to_string()).collect();
+            output.push_str(&format!(
+                "{}\n",
+                format!("Import depth {}: {}", chain.len() - 1, names.join(" -> ")).dimmed()
+            ));
+        }
+
+        let (errors, warnings, infos) = (
+            result.errors.len(),
+            result.warnings.len(),
+            result.infos.len(),
+        );
+        let summary = format!(
+            "{} {}, {} {}, {} {}",
+            errors,
+            plural(errors, "error", "errors"),
+            warnings,
+            plural(warnings, "warning", "warnings"),
+            infos,
+            plural(infos, "info", "infos")
+        );
+        let summary = if !result.errors.is_empty() {
+            summary.red()
+        } else if !result.warnings.is_empty() {
+            summary.yellow()
+        } else {
+            summary.green()
+        };
+        output.push_str(&format!("{}\n", summary));
+
+        if let Some(baseline) = &result.baseline {
+            output.push_str(&format!(
+                "{}\n",
+                format!(
+                    "{} known {} hidden by the baseline",
+                    baseline.suppressed,
+                    plural(baseline.suppressed, "issue", "issues")
+                )
+                .dimmed()
+            ));
+            if !baseline.fixed.is_empty() {
+                output.push_str(&format!(
+                    "{}\n",
+                    format!(
+                        "{} baseline {} fixed; rewrite the baseline to drop {}:",
+                        baseline.fixed.len(),
+                        plural(baseline.fixed.len(), "entry is", "entries are"),
+                        plural(baseline.fixed.len(), "it", "them")
+                    )
+                    .green()
+                ));
+                for entry in &baseline.fixed {
+                    output.push_str(&format!(
+                        "  [{}] {}: {}\n",
+                        entry.rule, entry.file, entry.message
+                    ));
+                }
+            }
+        }
+
+        let fixable = result.diagnostics().filter(|d| d.fix.is_some()).count();
+        if fixable > 0 {
+            output.push_str(&format!(
+                "{}\n",
+                format!("{} fixable with --fix", fixable).dimmed()
+            ));
+        }
+
+        Ok(output)
+    }
+}
+
+pub struct JsonReport {
+    pretty: bool,
+}
+
+impl Default for JsonReport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JsonReport {
+    pub fn new() -> Self {
+        Self { pretty: true }
+    }
+
+    pub fn with_pretty(mut self, pretty: bool) -> Self {
+        self.pretty = pretty;
+        self
+    }
+}
+
+impl ReportFormatter for JsonReport {
+    fn render(&self, result: &DiagnosticResult) -> Result<String> {
+        let json = if self.pretty {
+            serde_json::to_string_pretty(result)?
+        } else {
+            serde_json::to_string(result)?
+        };
+        Ok(format!("{}\n", json))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::PerformanceMetrics;
+
+    #[test]
+    fn summary_counts_are_pluralized() {
+        let mut result = DiagnosticResult {
+            errors: Vec::new(),
+            warnings: Vec::new(),
+            infos: Vec::new(),
+            suggestions: Vec::new(),
+            metrics: PerformanceMetrics::default(),
+            baseline: None,
+        };
+        result.push(Diagnostic::new(DiagnosticLevel::Error, "a", "one"));
+        result.push(Diagnostic::new(DiagnosticLevel::Info, "b", "two"));
+        result.push(Diagnostic::new(DiagnosticLevel::Info, "b", "three"));
+
+        let output = TextReport::new().render(&result).unwrap();
+        assert!(
+            output.contains("1 error, 0 warnings, 2 infos"),
+            "{}",
+            output
+        );
+    }
+}
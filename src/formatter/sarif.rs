@@ -0,0 +1,277 @@
+use super::report::ReportFormatter;
+use crate::analyzer::{Diagnostic, DiagnosticLevel, DiagnosticResult, Fix, RuleRegistry};
+use anyhow::Result;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+struct RuleMetadata {
+    id: String,
+    description: String,
+    explanation: String,
+    level: DiagnosticLevel,
+}
+
+/// SARIF 2.1.0 log with one run, for code scanning dashboards.
+pub struct SarifReport {
+    rules: Vec<RuleMetadata>,
+    project_root: Option<PathBuf>,
+}
+
+impl SarifReport {
+    pub fn new(registry: &RuleRegistry) -> Self {
+        Self {
+            rules: registry
+                .rules()
+                .map(|rule| RuleMetadata {
+                    id: rule.id().to_string(),
+                    description: rule.description().to_string(),
+                    explanation: rule.explanation().to_string(),
+                    level: rule.default_level(),
+                })
+                .collect(),
+            project_root: None,
+        }
+    }
+
+    /// Writes files under `root` as URIs relative to it.
+    pub fn with_project_root<P: AsRef<Path>>(mut self, root: P) -> Self {
+        let root = root.as_ref();
+        self.project_root = Some(root.canonicalize().unwrap_or_else(|_| root.to_path_buf()));
+        self
+    }
+
+    fn rule(&self, rule: &RuleMetadata) -> Value {
+        json!({
+            "id": rule.id,
+            "shortDescription": { "text": rule.description },
+            "fullDescription": { "text": rule.explanation },
+            "defaultConfiguration": { "level": level(rule.level) },
+        })
+    }
+
+    fn result(&self, diagnostic: &Diagnostic) -> Value {
+        let mut result = json!({
+            "ruleId": diagnostic.code,
+            "level": level(diagnostic.level),
+            "message": { "text": diagnostic.message },
+        });
+
+        if let Some(index) = self.rules.iter().position(|r| r.id == diagnostic.code) {
+            result["ruleIndex"] = json!(index);
+        }
+
+        if let Some(file) = &diagnostic.file {
+            let mut location = json!({ "artifactLocation": { "uri": self.uri(Path::new(file)) } });
+            if let Some(line) = diagnostic.line {
+                location["region"] = json!({ "startLine": line });
+                if let Some(column) = diagnostic.column {
+                    location["region"]["startColumn"] = json!(column);
+                }
+            }
+            result["locations"] = json!([{ "physicalLocation": location }]);
+        }
+
+        if let Some(fix) = &diagnostic.fix {
+            result["fixes"] = json!([self.fix(fix)]);
+        }
+
+        result
+    }
+
+    /// Paths under the project root become relative URIs, other relative
+    /// paths stay as they are and absolute paths become `file://` URIs.
+    fn uri(&self, path: &Path) -> String {
+        let absolute = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let path = match self
+            .project_root
+            .as_ref()
+            .and_then(|root| absolute.strip_prefix(root).ok())
+        {
+            Some(relative) => relative,
+            None => path.strip_prefix(".").unwrap_or(path),
+        };
+        let text = path.to_string_lossy().replace('\\', "/");
+        if path.is_absolute() {
+            format!("file://{}", text)
+        } else {
+            text
+        }
+    }
+
+    /// Edits become replacements addressed by byte offset. SARIF has no way to
+    /// express deleting a file, so deletions are left out.
+    fn fix(&self, fix: &Fix) -> Value {
+        let mut changes: Vec<(&Path, Vec<Value>)> = Vec::new();
+        for edit in &fix.edits {
+            let replacement = json!({
+                "deletedRegion": {
+                    "byteOffset": edit.start,
+                    "byteLength": edit.end - edit.start,
+                },
+                "insertedContent": { "text": edit.replacement },
+            });
+            match changes
+                .iter_mut()
+                .find(|(path, _)| *path == edit.file.as_path())
+            {
+                Some((_, replacements)) => replacements.push(replacement),
+                None => changes.push((&edit.file, vec![replacement])),
+            }
+        }
+
+        json!({
+            "description": { "text": fix.description },
+            "artifactChanges": changes
+                .into_iter()
+                .map(|(path, replacements)| json!({
+                    "artifactLocation": { "uri": self.uri(path) },
+                    "replacements": replacements,
+                }))
+                .collect::<Vec<_>>(),
+        })
+    }
+}
+
+impl ReportFormatter for SarifReport {
+    fn render(&self, result: &DiagnosticResult) -> Result<String> {
+        let log = json!({
+            "$schema": SARIF_SCHEMA,
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "ccat",
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                        "rules": self.rules.iter().map(|r| self.rule(r)).collect::<Vec<_>>(),
+                    }
+                },
+                "results": result.diagnostics().map(|d| self.result(d)).collect::<Vec<_>>(),
+            }],
+        });
+
+        Ok(format!("{}\n", serde_json::to_string_pretty(&log)?))
+    }
+}
+
+fn level(level: DiagnosticLevel) -> &'static str {
+    match level {
+        DiagnosticLevel::Error => "error",
+        DiagnosticLevel::Warning => "warning",
+        DiagnosticLevel::Info => "note",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::{PerformanceMetrics, TextEdit};
+
+    fn render(report: &SarifReport, diagnostics: Vec<Diagnostic>) -> Value {
+        let mut result = DiagnosticResult {
+            errors: Vec::new(),
+            warnings: Vec::new(),
+            infos: Vec::new(),
+            suggestions: Vec::new(),
+            metrics: PerformanceMetrics::default(),
+            baseline: None,
+        };
+        for diagnostic in diagnostics {
+            result.push(diagnostic);
+        }
+        serde_json::from_str(&report.render(&result).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn log_lists_rules_and_results() {
+        let registry = RuleRegistry::builtin();
+        let report = SarifReport::new(&registry);
+        let log = render(
+            &report,
+            vec![
+                Diagnostic::new(DiagnosticLevel::Info, "large-file", "Large")
+                    .with_file("./CLAUDE.md")
+                    .with_line(3)
+                    .with_column(7),
+                Diagnostic::new(DiagnosticLevel::Error, "circular-import", "Cycle")
+                    .with_file("docs/a.md"),
+                Diagnostic::new(DiagnosticLevel::Warning, "custom", "No file"),
+            ],
+        );
+
+        assert_eq!(log["$schema"], SARIF_SCHEMA);
+        assert_eq!(log["version"], "2.1.0");
+
+        let run = &log["runs"][0];
+        let rules = run["tool"]["driver"]["rules"].as_array().unwrap();
+        assert_eq!(rules.len(), registry.rules().count());
+        let large_file = rules.iter().position(|r| r["id"] == "large-file").unwrap();
+        assert_eq!(
+            rules[large_file]["defaultConfiguration"]["level"],
+            level(registry.get("large-file").unwrap().default_level())
+        );
+
+        let results = run["results"].as_array().unwrap();
+        let summary: Vec<(&str, &str)> = results
+            .iter()
+            .map(|r| (r["ruleId"].as_str().unwrap(), r["level"].as_str().unwrap()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("circular-import", "error"),
+                ("custom", "warning"),
+                ("large-file", "note"),
+            ]
+        );
+
+        let location = &results[0]["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "docs/a.md");
+        assert!(location.get("region").is_none());
+        assert!(results[1].get("locations").is_none());
+        assert!(results[1].get("ruleIndex").is_none());
+
+        assert_eq!(results[2]["ruleIndex"], large_file);
+        let location = &results[2]["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "CLAUDE.md");
+        assert_eq!(location["region"]["startLine"], 3);
+        assert_eq!(location["region"]["startColumn"], 7);
+    }
+
+    #[test]
+    fn uris_are_relative_to_the_project_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("docs")).unwrap();
+        std::fs::write(dir.path().join("docs/a.md"), "Text \n").unwrap();
+        std::fs::write(outside.path().join("b.md"), "").unwrap();
+
+        let inside = dir.path().join("docs/a.md");
+        let report = SarifReport::new(&RuleRegistry::new()).with_project_root(dir.path());
+        let log = render(
+            &report,
+            vec![
+                Diagnostic::new(DiagnosticLevel::Warning, "a", "Inside")
+                    .with_file(&inside)
+                    .with_fix(Fix::new("Trim").with_edit(TextEdit::delete(&inside, 4, 5))),
+                Diagnostic::new(DiagnosticLevel::Warning, "b", "Outside")
+                    .with_file(outside.path().join("b.md")),
+            ],
+        );
+
+        let results = log["runs"][0]["results"].as_array().unwrap();
+        let uri = |result: &Value| {
+            result["locations"][0]["physicalLocation"]["artifactLocation"]["uri"].clone()
+        };
+        assert_eq!(uri(&results[0]), "docs/a.md");
+        assert_eq!(
+            results[0]["fixes"][0]["artifactChanges"][0]["artifactLocation"]["uri"],
+            "docs/a.md"
+        );
+        let outside_uri = uri(&results[1]);
+        assert!(outside_uri.as_str().unwrap().starts_with("file://"));
+        assert!(outside_uri.as_str().unwrap().ends_with("/b.md"));
+    }
+}
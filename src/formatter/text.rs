@@ -72,14 +72,28 @@ impl TextFormatter {
 
         let mut output = String::from("  Imports:\n");
         for import in &file.imports {
-            let status = if import.resolved_path.is_some() {
+            let status = if import.is_resolved() {
                 "✓".green()
             } else {
                 "✗".red()
             };
+            let denied = if import.policy_violations.is_empty() {
+                String::new()
+            } else {
+                format!(", {}", "denied by import policy".red())
+            };
+            let alias = match &import.alias {
+                Some(alias) => format!(", via alias {}", alias.cyan()),
+                None => String::new(),
+            };
+            let matches = if import.is_glob() {
+                format!(", {} files", import.resolved_paths.len())
+            } else {
+                String::new()
+            };
             output.push_str(&format!(
-                "    {} {} (line {})\n",
-                status, import.path, import.line_number
+                "    {} {} (line {}{}{}{})\n",
+                status, import.path, import.line_number, matches, alias, denied
             ));
         }
         output
@@ -131,7 +145,7 @@ impl Formatter for TextFormatter {
     }
 }
 
-fn format_size(bytes: u64) -> String {
+pub(crate) fn format_size(bytes: u64) -> String {
     const UNITS: &[&str] = &["B", "KB", "MB", "GB"];
     let mut size = bytes as f64;
     let mut unit_index = 0;
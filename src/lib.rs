@@ -4,8 +4,8 @@ pub mod cli;
 pub mod core;
 pub mod formatter;
 
-pub use analyzer::{DiagnosticResult, Diagnostics};
-pub use core::{MemoryFile, MemoryType, Parser, Scanner};
+pub use analyzer::{DiagnosticResult, Diagnostics, Rule, RuleRegistry};
+pub use core::{Config, ImportGraph, ImportResolver, MemoryFile, MemoryType, Parser, Scanner};
 pub use formatter::Formatter;
 
 use anyhow::Result;
@@ -18,8 +18,15 @@ pub fn analyze<P: AsRef<Path>>(path: P) -> Result<Vec<MemoryFile>> {
 }
 
 pub fn diagnose<P: AsRef<Path>>(path: P) -> Result<DiagnosticResult> {
-    let files = analyze(path)?;
-    let diagnostics = Diagnostics::new();
-    let result = diagnostics.check(&files)?;
+    let path = path.as_ref();
+    let mut files = analyze(path)?;
+    let config = Config::load(path)?;
+    let mut resolver = ImportResolver::from_config(path, &config);
+    resolver.resolve_all(&mut files)?;
+    let graph = resolver.build_graph(&files)?;
+    let diagnostics = Diagnostics::new()
+        .with_config(config)
+        .with_project_root(path);
+    let result = diagnostics.check_with_graph(&files, &graph)?;
     Ok(result)
 }
@@ -1,22 +1,37 @@
 use anyhow::Result;
-use ccat::cli::{Cli, Commands};
+use ccat::cli::{exit_code, CheckFailed, Cli, Commands};
 use clap::Parser;
 use env_logger::Env;
 
-fn main() -> Result<()> {
+fn main() {
     env_logger::Builder::from_env(Env::default().default_filter_or("info")).init();
 
     let cli = Cli::parse();
 
+    if let Err(err) = run(cli) {
+        if err.is::<CheckFailed>() {
+            eprintln!("{}", err);
+            std::process::exit(exit_code::FINDINGS);
+        }
+        eprintln!("Error: {:?}", err);
+        std::process::exit(exit_code::ERROR);
+    }
+}
+
+fn run(cli: Cli) -> Result<()> {
     match cli.command {
         Some(Commands::Show(args)) => ccat::cli::commands::show::execute(args),
         Some(Commands::Diagnose(args)) => ccat::cli::commands::diagnose::execute(args),
+        Some(Commands::Rules(args)) => ccat::cli::commands::rules::execute(args),
         Some(Commands::Search(args)) => ccat::cli::commands::search::execute(args),
         Some(Commands::Diff(args)) => ccat::cli::commands::diff::execute(args),
         Some(Commands::Watch(args)) => ccat::cli::commands::watch::execute(args),
         Some(Commands::Export(args)) => ccat::cli::commands::export::execute(args),
+        Some(Commands::Locate(args)) => ccat::cli::commands::locate::execute(args),
         Some(Commands::Validate(args)) => ccat::cli::commands::validate::execute(args),
         Some(Commands::Init(args)) => ccat::cli::commands::init::execute(args),
+        Some(Commands::Graph(args)) => ccat::cli::commands::graph::execute(args),
+        Some(Commands::Migrate(args)) => ccat::cli::commands::migrate::execute(args),
         None => {
             // Default to show command with current directory
             let args = ccat::cli::args::ShowArgs {